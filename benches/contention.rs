@@ -0,0 +1,137 @@
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use criterion::{criterion_group, criterion_main, Bencher, Criterion};
+use lock_api::{RawRwLock, RawRwLockUpgrade};
+use one_shot_mutex::{RawOneShotMutex, RawOneShotRwLock};
+
+/// Number of threads polling the lock in the background in the `polled` benchmarks.
+const POLLERS: usize = 3;
+
+/// Runs `f` while [`POLLERS`] other threads repeatedly call `poll`.
+///
+/// If `poll` writes to the cache line of the lock even on failure, the line bounces between the cores.
+fn with_pollers(b: &mut Bencher<'_>, poll: impl Fn() + Sync, f: impl FnMut() -> bool) {
+    let stop = AtomicBool::new(false);
+    thread::scope(|s| {
+        for _ in 0..POLLERS {
+            s.spawn(|| {
+                while !stop.load(Ordering::Relaxed) {
+                    poll();
+                }
+            });
+        }
+        b.iter(f);
+        stop.store(true, Ordering::Relaxed);
+    });
+}
+
+fn try_lock(c: &mut Criterion) {
+    let mut group = c.benchmark_group("try_lock");
+
+    group.bench_function("locked", |b| {
+        let mutex = RawOneShotMutex::new();
+        mutex.lock();
+        b.iter(|| black_box(&mutex).try_lock());
+    });
+
+    group.bench_function("polled", |b| {
+        let mutex = RawOneShotMutex::new();
+        mutex.lock();
+        with_pollers(
+            b,
+            || {
+                black_box(black_box(&mutex).try_lock());
+            },
+            || black_box(&mutex).try_lock(),
+        );
+    });
+
+    group.finish();
+}
+
+fn try_lock_exclusive(c: &mut Criterion) {
+    let mut group = c.benchmark_group("try_lock_exclusive");
+
+    group.bench_function("shared", |b| {
+        let lock = RawOneShotRwLock::INIT;
+        lock.lock_shared();
+        b.iter(|| black_box(&lock).try_lock_exclusive());
+    });
+
+    group.bench_function("polled", |b| {
+        let lock = RawOneShotRwLock::INIT;
+        lock.lock_shared();
+        with_pollers(
+            b,
+            || {
+                black_box(black_box(&lock).try_lock_exclusive());
+            },
+            || black_box(&lock).try_lock_exclusive(),
+        );
+    });
+
+    group.finish();
+}
+
+fn try_upgrade(c: &mut Criterion) {
+    let mut group = c.benchmark_group("try_upgrade");
+
+    group.bench_function("shared", |b| {
+        let lock = RawOneShotRwLock::INIT;
+        lock.lock_upgradable();
+        lock.lock_shared();
+        b.iter(|| unsafe { black_box(&lock).try_upgrade() });
+    });
+
+    group.bench_function("polled", |b| {
+        let lock = RawOneShotRwLock::INIT;
+        lock.lock_upgradable();
+        lock.lock_shared();
+        with_pollers(
+            b,
+            || {
+                black_box(black_box(&lock).try_lock_exclusive());
+            },
+            || unsafe { black_box(&lock).try_upgrade() },
+        );
+    });
+
+    group.finish();
+}
+
+fn try_lock_shared(c: &mut Criterion) {
+    let mut group = c.benchmark_group("try_lock_shared");
+
+    group.bench_function("exclusive", |b| {
+        let lock = RawOneShotRwLock::INIT;
+        lock.lock_exclusive();
+        b.iter(|| black_box(&lock).try_lock_shared());
+    });
+
+    group.bench_function("write_heavy", |b| {
+        let lock = RawOneShotRwLock::INIT;
+        b.iter(|| {
+            let lock = black_box(&lock);
+            lock.lock_exclusive();
+            for _ in 0..8 {
+                black_box(lock.try_lock_shared());
+            }
+            unsafe {
+                lock.unlock_exclusive();
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    try_lock_shared,
+    try_lock,
+    try_lock_exclusive,
+    try_upgrade
+);
+criterion_main!(benches);
@@ -0,0 +1,218 @@
+use core::fmt;
+
+use lock_api::{GuardSend, RawMutex, RawMutexFair};
+
+use crate::contention::contended_abort;
+use crate::RawOneShotMutex;
+
+/// A one-shot mutex that aborts instead of panicking on contention.
+///
+/// This behaves like [`RawOneShotMutex`], except that [`lock`] aborts the program if the mutex is already locked.
+/// The contention is still reported to the [`ContentionReporter`] and the panic handler prints the message before aborting.
+///
+/// A contention panic can be caught with [`catch_unwind`], after which the program may continue with a lock that is in an unexpected state.
+/// Pick this mutex if contention on it means that the program is broken beyond recovery, for example, for locks in a kernel's trap handling.
+/// With `panic = "abort"`, both mutexes behave the same.
+///
+/// Aborting is implemented by panicking inside of a function that cannot unwind, which also works in `no_std` environments.
+///
+/// [`lock`]: Self::lock
+/// [`ContentionReporter`]: crate::ContentionReporter
+/// [`catch_unwind`]: https://doc.rust-lang.org/std/panic/fn.catch_unwind.html
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::OneShotMutexAbort;
+///
+/// static X: OneShotMutexAbort<i32> = OneShotMutexAbort::new(42);
+///
+/// let x = X.lock();
+///
+/// // This aborts instead of deadlocking.
+/// // let x2 = X.lock();
+/// ```
+pub struct RawOneShotMutexAbort {
+    inner: RawOneShotMutex,
+}
+
+impl RawOneShotMutexAbort {
+    /// Creates a new unlocked mutex.
+    ///
+    /// This is equivalent to [`RawMutex::INIT`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::RawOneShotMutexAbort;
+    ///
+    /// static X: RawOneShotMutexAbort = RawOneShotMutexAbort::new();
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self::INIT
+    }
+
+    /// Acquires this mutex.
+    ///
+    /// This is equivalent to [`RawMutex::lock`] but does not require the trait to be in scope.
+    ///
+    /// # Aborts
+    ///
+    /// This function aborts the program if the mutex is already locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::RawOneShotMutexAbort;
+    ///
+    /// let mutex = RawOneShotMutexAbort::new();
+    /// mutex.lock();
+    /// assert!(mutex.is_locked());
+    /// assert!(!mutex.try_lock());
+    /// unsafe { mutex.unlock() }
+    /// assert!(!mutex.is_locked());
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn lock(&self) {
+        <Self as RawMutex>::lock(self);
+    }
+
+    /// Attempts to acquire this mutex.
+    ///
+    /// This is equivalent to [`RawMutex::try_lock`] but does not require the trait to be in scope.
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    pub fn try_lock(&self) -> bool {
+        <Self as RawMutex>::try_lock(self)
+    }
+
+    /// Unlocks this mutex.
+    ///
+    /// This is equivalent to [`RawMutex::unlock`] but does not require the trait to be in scope.
+    ///
+    /// # Safety
+    ///
+    /// This method may only be called if the mutex is held in the current context, see [`RawMutex::unlock`].
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        unsafe { <Self as RawMutex>::unlock(self) }
+    }
+
+    /// Checks whether this mutex is currently locked.
+    ///
+    /// This is equivalent to [`RawMutex::is_locked`] but does not require the trait to be in scope.
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        <Self as RawMutex>::is_locked(self)
+    }
+}
+
+unsafe impl RawMutex for RawOneShotMutexAbort {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        inner: RawOneShotMutex::INIT,
+    };
+
+    type GuardMarker = GuardSend;
+
+    #[inline]
+    #[track_caller]
+    fn lock(&self) {
+        if !self.inner.try_lock_strong() {
+            contended_abort("lock", "RawOneShotMutexAbort", "already locked");
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    fn try_lock(&self) -> bool {
+        self.inner.try_lock()
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        unsafe { self.inner.unlock() }
+    }
+
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.inner.is_locked()
+    }
+}
+
+unsafe impl RawMutexFair for RawOneShotMutexAbort {
+    #[inline]
+    unsafe fn unlock_fair(&self) {
+        unsafe { self.unlock() }
+    }
+
+    #[inline]
+    unsafe fn bump(&self) {}
+}
+
+impl Default for RawOneShotMutexAbort {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RawOneShotMutexAbort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawOneShotMutexAbort")
+            .field("locked", &self.is_locked())
+            .finish()
+    }
+}
+
+/// A [`lock_api::Mutex`] based on [`RawOneShotMutexAbort`].
+pub type OneShotMutexAbort<T> = lock_api::Mutex<RawOneShotMutexAbort, T>;
+
+/// A [`lock_api::MutexGuard`] based on [`RawOneShotMutexAbort`].
+pub type OneShotMutexAbortGuard<'a, T> = lock_api::MutexGuard<'a, RawOneShotMutexAbort, T>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock() {
+        let mutex = OneShotMutexAbort::new(42);
+        let mut guard = mutex.lock();
+        assert_eq!(*guard, 42);
+        assert!(mutex.try_lock().is_none());
+
+        *guard += 1;
+        drop(guard);
+        let guard = mutex.lock();
+        assert_eq!(*guard, 43);
+    }
+
+    #[test]
+    fn construct() {
+        static STATIC: OneShotMutexAbort<i32> = OneShotMutexAbort::new(42);
+
+        let from: OneShotMutexAbort<i32> = 42.into();
+        assert_eq!(*STATIC.lock(), 42);
+        assert_eq!(*from.lock(), 42);
+    }
+
+    #[test]
+    fn raw() {
+        let mutex = RawOneShotMutexAbort::default();
+        mutex.lock();
+        assert!(mutex.is_locked());
+        assert!(!mutex.try_lock());
+        unsafe { mutex.unlock() }
+        assert!(!mutex.is_locked());
+        assert!(mutex.try_lock());
+    }
+
+    #[test]
+    fn data_ptr() {
+        let mutex = OneShotMutexAbort::new(42);
+        assert_eq!(unsafe { mutex.data_ptr().read() }, 42);
+    }
+}
@@ -0,0 +1,58 @@
+//! The atomic types used for the lock state.
+//!
+//! With the `loom` feature, these are created lazily, since loom's atomic types cannot be created in const contexts.
+//! With the `robust` feature, the lock states include the owning process and are stored in an `AtomicU64`.
+
+// Depending on the features, not all of these are used.
+#[cfg(all(not(feature = "loom"), feature = "robust"))]
+pub(crate) use core::sync::atomic::AtomicU64;
+#[cfg(not(feature = "loom"))]
+#[allow(unused_imports)]
+pub(crate) use core::sync::atomic::{AtomicBool, AtomicUsize};
+
+#[cfg(all(feature = "loom", feature = "robust"))]
+pub(crate) use self::lazy::AtomicU64;
+#[cfg(feature = "loom")]
+#[allow(unused_imports)]
+pub(crate) use self::lazy::{AtomicBool, AtomicUsize};
+
+#[cfg(feature = "loom")]
+#[allow(dead_code)]
+mod lazy {
+    use core::ops::Deref;
+    use std::sync::OnceLock;
+
+    macro_rules! lazy_atomic {
+        ($($atomic:ident($int:ty)),*) => {
+            $(
+                pub(crate) struct $atomic {
+                    init: $int,
+                    inner: OnceLock<loom::sync::atomic::$atomic>,
+                }
+
+                impl $atomic {
+                    pub const fn new(v: $int) -> Self {
+                        Self {
+                            init: v,
+                            inner: OnceLock::new(),
+                        }
+                    }
+                }
+
+                impl Deref for $atomic {
+                    type Target = loom::sync::atomic::$atomic;
+
+                    #[inline]
+                    fn deref(&self) -> &Self::Target {
+                        self.inner
+                            .get_or_init(|| loom::sync::atomic::$atomic::new(self.init))
+                    }
+                }
+            )*
+        };
+    }
+
+    lazy_atomic!(AtomicBool(bool), AtomicUsize(usize));
+    #[cfg(feature = "robust")]
+    lazy_atomic!(AtomicU64(u64));
+}
@@ -0,0 +1,223 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+#[cfg(feature = "stable-deref")]
+use stable_deref_trait::StableDeref;
+
+use crate::{
+    OneShotMutexGuard, OneShotRwLockReadGuard, OneShotRwLockUpgradableReadGuard,
+    OneShotRwLockWriteGuard,
+};
+
+/// A guard wrapper that implements [`Borrow`] and [`AsRef`].
+///
+/// The guards of [`lock_api`] only implement [`Deref`] and [`DerefMut`].
+/// Wrapping them in a `BorrowGuard` allows passing them to APIs that require `Borrow<T>` or `AsRef<T>`.
+/// For [`OneShotMutexGuard`] and [`OneShotRwLockWriteGuard`], this also implements [`BorrowMut`] and [`AsMut`].
+///
+/// With the `stable-deref` feature, this also implements [`StableDeref`](https://docs.rs/stable_deref_trait/latest/stable_deref_trait/trait.StableDeref.html).
+/// This allows building owning references over locked data.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Borrow;
+///
+/// use one_shot_mutex::{BorrowGuard, OneShotMutex};
+///
+/// fn len(s: impl Borrow<String>) -> usize {
+///     s.borrow().len()
+/// }
+///
+/// let mutex = OneShotMutex::new(String::from("foo"));
+/// let guard = BorrowGuard::new(mutex.lock());
+/// assert_eq!(len(guard), 3);
+/// ```
+pub struct BorrowGuard<G>(G);
+
+impl<G> BorrowGuard<G> {
+    /// Wraps a guard.
+    #[inline]
+    pub const fn new(guard: G) -> Self {
+        Self(guard)
+    }
+
+    /// Unwraps the guard.
+    #[inline]
+    pub fn into_inner(self) -> G {
+        self.0
+    }
+}
+
+impl<G> From<G> for BorrowGuard<G> {
+    #[inline]
+    fn from(guard: G) -> Self {
+        Self(guard)
+    }
+}
+
+impl<G: Deref> Deref for BorrowGuard<G> {
+    type Target = G::Target;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<G: DerefMut> DerefMut for BorrowGuard<G> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<G: fmt::Debug> fmt::Debug for BorrowGuard<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<G: fmt::Display> fmt::Display for BorrowGuard<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+// These impls cannot be generic over `G: Deref<Target = T>`,
+// since they would overlap with the blanket `impl<T> Borrow<T> for T`.
+macro_rules! impl_borrow {
+    ($($guard:ident),*) => {
+        $(
+            impl<T: ?Sized> Borrow<T> for BorrowGuard<$guard<'_, T>> {
+                #[inline]
+                fn borrow(&self) -> &T {
+                    self
+                }
+            }
+
+            impl<T: ?Sized> AsRef<T> for BorrowGuard<$guard<'_, T>> {
+                #[inline]
+                fn as_ref(&self) -> &T {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_borrow_mut {
+    ($($guard:ident),*) => {
+        $(
+            impl<T: ?Sized> BorrowMut<T> for BorrowGuard<$guard<'_, T>> {
+                #[inline]
+                fn borrow_mut(&mut self) -> &mut T {
+                    self
+                }
+            }
+
+            impl<T: ?Sized> AsMut<T> for BorrowGuard<$guard<'_, T>> {
+                #[inline]
+                fn as_mut(&mut self) -> &mut T {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_borrow!(
+    OneShotMutexGuard,
+    OneShotRwLockReadGuard,
+    OneShotRwLockUpgradableReadGuard,
+    OneShotRwLockWriteGuard
+);
+impl_borrow_mut!(OneShotMutexGuard, OneShotRwLockWriteGuard);
+
+// SAFETY: The guards dereference to the data inside the lock,
+// which does not move while the lock is borrowed by the guard.
+#[cfg(feature = "stable-deref")]
+unsafe impl<T: ?Sized> StableDeref for BorrowGuard<OneShotMutexGuard<'_, T>> {}
+#[cfg(feature = "stable-deref")]
+unsafe impl<T: ?Sized> StableDeref for BorrowGuard<OneShotRwLockReadGuard<'_, T>> {}
+#[cfg(feature = "stable-deref")]
+unsafe impl<T: ?Sized> StableDeref for BorrowGuard<OneShotRwLockUpgradableReadGuard<'_, T>> {}
+#[cfg(feature = "stable-deref")]
+unsafe impl<T: ?Sized> StableDeref for BorrowGuard<OneShotRwLockWriteGuard<'_, T>> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OneShotMutex, OneShotRwLock};
+
+    fn get<B: Borrow<i32>>(b: B) -> i32 {
+        *b.borrow()
+    }
+
+    fn increment<B: BorrowMut<i32>>(mut b: B) {
+        *b.borrow_mut() += 1;
+    }
+
+    #[test]
+    fn mutex() {
+        let mutex = OneShotMutex::new(42);
+        increment(BorrowGuard::new(mutex.lock()));
+        assert_eq!(get(BorrowGuard::new(mutex.lock())), 43);
+        assert!(!mutex.is_locked());
+
+        let mut guard = BorrowGuard::new(mutex.lock());
+        *guard.as_mut() += 1;
+        assert_eq!(*guard.as_ref(), 44);
+    }
+
+    #[test]
+    #[cfg(feature = "stable-deref")]
+    fn stable_deref() {
+        /// A minimal owning reference.
+        struct OwningRef<O, U: ?Sized> {
+            _owner: O,
+            reference: *const U,
+        }
+
+        impl<O: StableDeref, U: ?Sized> OwningRef<O, U> {
+            fn new(owner: O, f: impl FnOnce(&O::Target) -> &U) -> Self {
+                let reference = f(&owner);
+                Self {
+                    reference,
+                    _owner: owner,
+                }
+            }
+        }
+
+        impl<O, U: ?Sized> Deref for OwningRef<O, U> {
+            type Target = U;
+
+            fn deref(&self) -> &U {
+                // SAFETY: `O: StableDeref` guarantees that the reference stays valid when moving the owner.
+                unsafe { &*self.reference }
+            }
+        }
+
+        let mutex = OneShotMutex::new((1, 2));
+        let owning_ref = OwningRef::new(BorrowGuard::new(mutex.lock()), |x| &x.1);
+        assert_eq!(*owning_ref, 2);
+        assert!(mutex.is_locked());
+        drop(owning_ref);
+        assert!(!mutex.is_locked());
+
+        let lock = OneShotRwLock::new((1, 2));
+        let owning_ref = OwningRef::new(BorrowGuard::new(lock.read()), |x| &x.0);
+        let owning_ref2 = OwningRef::new(BorrowGuard::new(lock.read()), |x| &x.1);
+        assert_eq!(*owning_ref + *owning_ref2, 3);
+    }
+
+    #[test]
+    fn rwlock() {
+        let lock = OneShotRwLock::new(42);
+        increment(BorrowGuard::new(lock.write()));
+        assert_eq!(get(BorrowGuard::new(lock.read())), 43);
+        assert_eq!(get(BorrowGuard::new(lock.upgradable_read())), 43);
+        assert!(!lock.is_locked());
+    }
+}
@@ -0,0 +1,312 @@
+use core::fmt;
+use core::panic::Location;
+
+use crate::global::Global;
+
+/// Information about a contended lock operation.
+///
+/// This is passed to [`ContentionReporter::report`] right before the lock panics.
+/// Its [`Display`] implementation formats the panic message.
+///
+/// [`Display`]: fmt::Display
+#[derive(Clone, Copy, Debug)]
+pub struct ContentionInfo {
+    operation: &'static str,
+    lock: &'static str,
+    type_name: Option<&'static str>,
+    reason: &'static str,
+    location: &'static Location<'static>,
+    held_at: Option<&'static Location<'static>>,
+}
+
+impl ContentionInfo {
+    /// Returns the name of the contended operation, such as `lock` or `lock_shared`.
+    #[inline]
+    pub fn operation(&self) -> &'static str {
+        self.operation
+    }
+
+    /// Returns the name of the contended lock type, such as `RawOneShotMutex`.
+    #[inline]
+    pub fn lock(&self) -> &'static str {
+        self.lock
+    }
+
+    /// Returns the name of the protected type, if known.
+    ///
+    /// This is only known for [`NamedOneShotMutex`] and [`NamedOneShotRwLock`].
+    ///
+    /// [`NamedOneShotMutex`]: crate::NamedOneShotMutex
+    /// [`NamedOneShotRwLock`]: crate::NamedOneShotRwLock
+    #[inline]
+    pub fn type_name(&self) -> Option<&'static str> {
+        self.type_name
+    }
+
+    /// Returns why the operation could not proceed, such as `already locked`.
+    #[inline]
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+
+    /// Returns the location of the contended operation.
+    #[inline]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns the location at which the lock is held, if known.
+    ///
+    /// This is only recorded for [`RawOneShotMutex`] with the `track-location` feature.
+    ///
+    /// [`RawOneShotMutex`]: crate::RawOneShotMutex
+    #[inline]
+    pub fn held_at(&self) -> Option<&'static Location<'static>> {
+        self.held_at
+    }
+}
+
+impl fmt::Display for ContentionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "called `{}` on a `{}", self.operation, self.lock)?;
+
+        if let Some(type_name) = self.type_name {
+            write!(f, "<{type_name}>")?;
+        }
+
+        write!(f, "` that is {}", self.reason)?;
+
+        if let Some(held_at) = self.held_at {
+            write!(f, " at {held_at}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A reporter that is notified of contention before the lock panics.
+///
+/// This allows forwarding contention to a logging framework such as [`log`] or [`defmt`] without this crate depending on either.
+/// See [`set_contention_reporter`].
+///
+/// Alternatively, with the `defmt` feature, locks panic through `defmt::panic!` instead of [`panic!`].
+/// The message stays the same, but is formatted by defmt instead of `core::fmt`, which saves code size on embedded targets.
+/// It is prefixed with the location of the contended call, which `defmt::panic!` does not report on its own.
+///
+/// [`log`]: https://docs.rs/log
+/// [`defmt`]: https://docs.rs/defmt
+pub trait ContentionReporter: Sync {
+    /// Reports contention.
+    fn report(&self, info: &ContentionInfo);
+}
+
+static REPORTER: Global<dyn ContentionReporter> = Global::new();
+
+/// Sets the global contention reporter.
+///
+/// The reporter can only be set once.
+///
+/// # Errors
+///
+/// This function fails if a reporter has already been set.
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{set_contention_reporter, ContentionInfo, ContentionReporter};
+///
+/// struct Reporter;
+///
+/// impl ContentionReporter for Reporter {
+///     fn report(&self, info: &ContentionInfo) {
+///         eprintln!("contention at {}: {info}", info.location());
+///     }
+/// }
+///
+/// set_contention_reporter(&Reporter).unwrap();
+/// ```
+pub fn set_contention_reporter(
+    reporter: &'static dyn ContentionReporter,
+) -> Result<(), SetContentionReporterError> {
+    if REPORTER.set(reporter) {
+        Ok(())
+    } else {
+        Err(SetContentionReporterError(()))
+    }
+}
+
+/// The error returned by [`set_contention_reporter`] if a reporter has already been set.
+#[derive(Debug)]
+pub struct SetContentionReporterError(());
+
+impl fmt::Display for SetContentionReporterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a contention reporter has already been set")
+    }
+}
+
+impl core::error::Error for SetContentionReporterError {}
+
+/// The error returned by the `checked_*` methods if the lock is already locked.
+///
+/// See [`OneShotMutexExt::checked_lock`], [`OneShotRwLockExt::checked_read`], and [`OneShotRwLockExt::checked_write`].
+///
+/// [`OneShotMutexExt::checked_lock`]: crate::OneShotMutexExt::checked_lock
+/// [`OneShotRwLockExt::checked_read`]: crate::OneShotRwLockExt::checked_read
+/// [`OneShotRwLockExt::checked_write`]: crate::OneShotRwLockExt::checked_write
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Contended(());
+
+impl Contended {
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Self(())
+    }
+}
+
+impl fmt::Display for Contended {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the lock is already locked")
+    }
+}
+
+impl core::error::Error for Contended {}
+
+/// Reports contention and panics.
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn contended(operation: &'static str, lock: &'static str, reason: &'static str) -> ! {
+    contended_held_at(operation, lock, reason, None)
+}
+
+/// Reports contention with the location at which the lock is held and panics.
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn contended_held_at(
+    operation: &'static str,
+    lock: &'static str,
+    reason: &'static str,
+    held_at: Option<&'static Location<'static>>,
+) -> ! {
+    let info = ContentionInfo {
+        operation,
+        lock,
+        type_name: None,
+        reason,
+        location: Location::caller(),
+        held_at,
+    };
+
+    fail(&info)
+}
+
+/// Reports contention on a lock protecting `type_name` and panics.
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn contended_typed(
+    operation: &'static str,
+    lock: &'static str,
+    type_name: &'static str,
+    reason: &'static str,
+) -> ! {
+    let info = ContentionInfo {
+        operation,
+        lock,
+        type_name: Some(type_name),
+        reason,
+        location: Location::caller(),
+        held_at: None,
+    };
+
+    fail(&info)
+}
+
+/// Reports contention and aborts.
+///
+/// This panics inside of a function that cannot unwind, which prints the panic message and aborts.
+/// Unlike [`std::process::abort`], this also works in `no_std` environments.
+///
+/// [`std::process::abort`]: https://doc.rust-lang.org/std/process/fn.abort.html
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub(crate) fn contended_abort(
+    operation: &'static str,
+    lock: &'static str,
+    reason: &'static str,
+) -> ! {
+    extern "C" fn fail_nounwind(info: &ContentionInfo) -> ! {
+        fail(info)
+    }
+
+    let info = ContentionInfo {
+        operation,
+        lock,
+        type_name: None,
+        reason,
+        location: Location::caller(),
+        held_at: None,
+    };
+
+    fail_nounwind(&info)
+}
+
+/// Reports contention to the global reporter and panics.
+///
+/// All contention panics go through this function.
+/// With the `defmt` feature, this panics through [`defmt::panic!`] with the same message as the [`Display`] implementation,
+/// which avoids the `core::fmt` machinery.
+/// Since `defmt::panic!` does not report the caller's location, the message is prefixed with the contended call site.
+///
+/// [`defmt::panic!`]: https://docs.rs/defmt/latest/defmt/macro.panic.html
+/// [`Display`]: fmt::Display
+#[track_caller]
+fn fail(info: &ContentionInfo) -> ! {
+    if let Some(reporter) = REPORTER.get() {
+        reporter.report(info);
+    }
+
+    #[cfg(feature = "defmt")]
+    {
+        let (open, type_name, close) = match info.type_name {
+            Some(type_name) => ("<", type_name, ">"),
+            None => ("", "", ""),
+        };
+
+        match info.held_at {
+            Some(held_at) => defmt::panic!(
+                "{=str}:{=u32}:{=u32}: called `{=str}` on a `{=str}{=str}{=str}{=str}` that is {=str} at {=str}:{=u32}:{=u32}",
+                info.location.file(),
+                info.location.line(),
+                info.location.column(),
+                info.operation,
+                info.lock,
+                open,
+                type_name,
+                close,
+                info.reason,
+                held_at.file(),
+                held_at.line(),
+                held_at.column(),
+            ),
+            None => defmt::panic!(
+                "{=str}:{=u32}:{=u32}: called `{=str}` on a `{=str}{=str}{=str}{=str}` that is {=str}",
+                info.location.file(),
+                info.location.line(),
+                info.location.column(),
+                info.operation,
+                info.lock,
+                open,
+                type_name,
+                close,
+                info.reason,
+            ),
+        }
+    }
+
+    #[cfg(not(feature = "defmt"))]
+    panic!("{info}");
+}
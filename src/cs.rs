@@ -0,0 +1,197 @@
+use critical_section::CriticalSection;
+
+use crate::{OneShotRwLock, OneShotRwLockReadGuard, OneShotRwLockWriteGuard};
+
+/// A one-shot lock that can only be accessed within a critical section.
+///
+/// This combines the token-based access model of [`critical_section::Mutex`] with one-shot contention detection.
+/// Like `critical_section::Mutex<RefCell<T>>`, [`borrow`] allows any number of shared borrows and [`lock`] allows a single exclusive borrow.
+/// Unlike a `RefCell`, the borrows are tracked by a [`RawOneShotRwLock`], which panics on conflicting re-entrant access.
+///
+/// Like [`critical_section::Mutex`], this mutex is [`Sync`] if `T` is [`Send`],
+/// since only the thread that is in the critical section can access the data.
+/// This allows storing types such as [`Cell`] in a `static`.
+///
+/// [`borrow`]: Self::borrow
+/// [`lock`]: Self::lock
+/// [`RawOneShotRwLock`]: crate::RawOneShotRwLock
+/// [`Cell`]: core::cell::Cell
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::CsOneShotMutex;
+///
+/// static X: CsOneShotMutex<i32> = CsOneShotMutex::new(42);
+///
+/// critical_section::with(|cs| {
+///     let mut x = X.lock(cs);
+///     *x += 1;
+///
+///     // This panics instead of aliasing.
+///     // let x2 = X.borrow(cs);
+/// });
+/// ```
+pub struct CsOneShotMutex<T: ?Sized> {
+    inner: OneShotRwLock<T>,
+}
+
+// SAFETY: The data can only be accessed with a `CriticalSection` token, which is neither `Send` nor `Sync`.
+// Only one thread can be in a critical section at a time, so shared borrows are never used by multiple threads at the same time.
+// Moving a mutable borrow to another thread requires `T: Send`.
+unsafe impl<T: ?Sized + Send> Sync for CsOneShotMutex<T> {}
+
+impl<T> CsOneShotMutex<T> {
+    /// Creates a new mutex in an unlocked state ready for use.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self {
+            inner: OneShotRwLock::new(value),
+        }
+    }
+
+    /// Consumes this mutex, returning the underlying data.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+}
+
+impl<T: ?Sized> CsOneShotMutex<T> {
+    /// Borrows the data immutably for the duration of the critical section.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the data is already borrowed mutably.
+    #[inline]
+    #[track_caller]
+    pub fn borrow<'cs>(&'cs self, _cs: CriticalSection<'cs>) -> OneShotRwLockReadGuard<'cs, T> {
+        self.inner.read()
+    }
+
+    /// Attempts to borrow the data immutably for the duration of the critical section.
+    ///
+    /// Returns `None` if the data is already borrowed mutably.
+    #[inline]
+    pub fn try_borrow<'cs>(
+        &'cs self,
+        _cs: CriticalSection<'cs>,
+    ) -> Option<OneShotRwLockReadGuard<'cs, T>> {
+        self.inner.try_read()
+    }
+
+    /// Borrows the data mutably for the duration of the critical section.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the data is already borrowed.
+    #[inline]
+    #[track_caller]
+    pub fn lock<'cs>(&'cs self, _cs: CriticalSection<'cs>) -> OneShotRwLockWriteGuard<'cs, T> {
+        self.inner.write()
+    }
+
+    /// Attempts to borrow the data mutably for the duration of the critical section.
+    ///
+    /// Returns `None` if the data is already borrowed.
+    #[inline]
+    pub fn try_lock<'cs>(
+        &'cs self,
+        _cs: CriticalSection<'cs>,
+    ) -> Option<OneShotRwLockWriteGuard<'cs, T>> {
+        self.inner.try_write()
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the mutex mutably, no critical section is needed.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn lock() {
+        let mutex = CsOneShotMutex::new(42);
+
+        critical_section::with(|cs| {
+            let mut guard = mutex.lock(cs);
+            assert_eq!(*guard, 42);
+            *guard += 1;
+        });
+
+        critical_section::with(|cs| {
+            let guard = mutex.lock(cs);
+            assert_eq!(*guard, 43);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn lock_panic() {
+        let mutex = CsOneShotMutex::new(42);
+
+        critical_section::with(|cs| {
+            let _guard = mutex.lock(cs);
+            let _guard2 = mutex.lock(cs);
+        });
+    }
+
+    #[test]
+    fn borrow() {
+        let mutex = CsOneShotMutex::new(42);
+
+        critical_section::with(|cs| {
+            let guard = mutex.borrow(cs);
+            let guard2 = mutex.borrow(cs);
+            assert_eq!(*guard, 42);
+            assert_eq!(*guard2, 42);
+            assert!(mutex.try_lock(cs).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn borrow_panic() {
+        let mutex = CsOneShotMutex::new(42);
+
+        critical_section::with(|cs| {
+            let _guard = mutex.lock(cs);
+            let _guard2 = mutex.borrow(cs);
+        });
+    }
+
+    #[test]
+    fn cell() {
+        static CELL: CsOneShotMutex<Cell<i32>> = CsOneShotMutex::new(Cell::new(42));
+
+        critical_section::with(|cs| {
+            let cell = CELL.borrow(cs);
+            cell.set(cell.get() + 1);
+            assert_eq!(CELL.borrow(cs).get(), 43);
+        });
+    }
+
+    #[test]
+    fn try_lock() {
+        let mutex = CsOneShotMutex::new(42);
+
+        critical_section::with(|cs| {
+            let guard = mutex.try_lock(cs).unwrap();
+            assert_eq!(*guard, 42);
+            assert!(mutex.try_lock(cs).is_none());
+            assert!(mutex.try_borrow(cs).is_none());
+        });
+
+        critical_section::with(|cs| {
+            assert!(mutex.try_borrow(cs).is_some());
+        });
+    }
+}
@@ -0,0 +1,68 @@
+/// A successful lock operation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LockEvent {
+    /// A mutex was locked.
+    Lock,
+    /// A mutex was unlocked.
+    Unlock,
+    /// A readers-writer lock was locked shared.
+    LockShared,
+    /// A readers-writer lock was unlocked shared.
+    UnlockShared,
+    /// A readers-writer lock was locked exclusively.
+    LockExclusive,
+    /// A readers-writer lock was unlocked exclusively.
+    UnlockExclusive,
+    /// A readers-writer lock was locked upgradably.
+    LockUpgradable,
+    /// A readers-writer lock was unlocked upgradably.
+    UnlockUpgradable,
+    /// An upgradable lock was upgraded to an exclusive lock.
+    Upgrade,
+    /// An exclusive lock was downgraded to a shared lock.
+    Downgrade,
+    /// An upgradable lock was downgraded to a shared lock.
+    DowngradeUpgradable,
+    /// An exclusive lock was downgraded to an upgradable lock.
+    DowngradeToUpgradable,
+}
+
+/// Notifies the enabled instrumentation of a successful lock operation.
+///
+/// With the `tracing` feature, this emits a `TRACE` event.
+#[inline(always)]
+#[track_caller]
+pub(crate) fn emit<L>(lock: &L, event: LockEvent) {
+    #[cfg(feature = "replay")]
+    crate::replay::record(core::ptr::from_ref(lock).addr(), event);
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(
+        lock = core::ptr::from_ref(lock).addr(),
+        event = ?event,
+        location = %core::panic::Location::caller(),
+        "lock event",
+    );
+
+    #[cfg(not(any(feature = "replay", feature = "tracing")))]
+    let _ = (lock, event);
+}
+
+/// Notifies the enabled instrumentation of a failed attempt to acquire a lock.
+///
+/// With the `tracing` feature, this emits a `WARN` event,
+/// since failing to acquire a one-shot lock usually indicates a bug.
+#[inline(always)]
+#[track_caller]
+pub(crate) fn emit_failed<L>(lock: &L, operation: &'static str) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(
+        lock = core::ptr::from_ref(lock).addr(),
+        operation,
+        location = %core::panic::Location::caller(),
+        "failed to acquire lock",
+    );
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = (lock, operation);
+}
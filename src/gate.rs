@@ -0,0 +1,187 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::contention::contended;
+
+const INCOMPLETE: u8 = 0;
+const RUNNING: u8 = 1;
+const COMPLETE: u8 = 2;
+
+/// A one-shot gate that runs a closure exactly once and panics instead of blocking on contention.
+///
+/// Once [`run_once`] has completed, the gate is closed for good and further calls are no-ops.
+/// Calling [`run_once`] while another call is still running panics instead of blocking.
+/// This is useful in situations where contention would be a bug,
+/// such as in single-threaded programs that would deadlock on contention.
+///
+/// If the closure panics, the gate is not closed and [`run_once`] may be called again.
+///
+/// [`run_once`]: Self::run_once
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::OneShotGate;
+///
+/// static INIT: OneShotGate = OneShotGate::new();
+///
+/// let mut runs = 0;
+/// INIT.run_once(|| runs += 1);
+///
+/// // This is a no-op.
+/// INIT.run_once(|| runs += 1);
+/// assert_eq!(runs, 1);
+///
+/// // This panics instead of deadlocking.
+/// // INIT.run_once(|| INIT.run_once(|| {}));
+/// ```
+pub struct OneShotGate {
+    state: AtomicU8,
+}
+
+impl OneShotGate {
+    /// Creates a new open gate.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(INCOMPLETE),
+        }
+    }
+
+    /// Runs `f` if no call to this method has completed yet.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if another call to this method is still running.
+    #[inline]
+    #[track_caller]
+    pub fn run_once<F: FnOnce()>(&self, f: F) {
+        match self
+            .state
+            .compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Acquire)
+        {
+            Ok(_) => {}
+            Err(COMPLETE) => return,
+            Err(_) => contended("run_once", "OneShotGate", "already running"),
+        }
+
+        struct Reset<'a>(&'a AtomicU8);
+
+        impl Drop for Reset<'_> {
+            fn drop(&mut self) {
+                self.0.store(INCOMPLETE, Ordering::Release);
+            }
+        }
+
+        // Reopen the gate if `f` panics.
+        let reset = Reset(&self.state);
+        f();
+        core::mem::forget(reset);
+
+        self.state.store(COMPLETE, Ordering::Release);
+    }
+
+    /// Returns `true` if a call to [`run_once`] has completed.
+    ///
+    /// [`run_once`]: Self::run_once
+    #[inline]
+    pub fn is_completed(&self) -> bool {
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+}
+
+impl Default for OneShotGate {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_once() {
+        let gate = OneShotGate::new();
+        assert!(!gate.is_completed());
+
+        let mut runs = 0;
+        gate.run_once(|| runs += 1);
+        assert_eq!(runs, 1);
+        assert!(gate.is_completed());
+
+        gate.run_once(|| runs += 1);
+        assert_eq!(runs, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn run_once_panic() {
+        let gate = OneShotGate::new();
+        gate.run_once(|| gate.run_once(|| {}));
+    }
+
+    #[test]
+    fn run_once_nested_after_completion() {
+        let gate = OneShotGate::new();
+        let other = OneShotGate::new();
+        gate.run_once(|| {});
+        other.run_once(|| gate.run_once(|| unreachable!()));
+        assert!(other.is_completed());
+    }
+
+    #[test]
+    fn run_once_race() {
+        use std::string::String;
+        use std::sync::atomic::AtomicUsize;
+        use std::sync::Barrier;
+        use std::thread;
+        use std::vec::Vec;
+
+        const THREADS: usize = 4;
+
+        let gate = OneShotGate::new();
+        let runs = AtomicUsize::new(0);
+        let barrier = Barrier::new(THREADS);
+
+        let results = thread::scope(|s| {
+            let handles = (0..THREADS)
+                .map(|_| {
+                    s.spawn(|| {
+                        barrier.wait();
+                        let mut ran = false;
+                        gate.run_once(|| {
+                            runs.fetch_add(1, Ordering::Relaxed);
+                            // Give the other threads a chance to observe the running closure.
+                            thread::yield_now();
+                            ran = true;
+                        });
+                        // Returning without running the closure means that the gate has been closed.
+                        assert!(ran || gate.is_completed());
+                        ran
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join())
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        assert!(gate.is_completed());
+
+        let mut winners = 0;
+        for result in results {
+            match result {
+                Ok(ran) => winners += usize::from(ran),
+                // Losing while the closure is still running panics.
+                Err(payload) => {
+                    let message = payload.downcast_ref::<String>().unwrap();
+                    assert!(message.contains("already running"), "{message}");
+                }
+            }
+        }
+        assert_eq!(winners, 1);
+    }
+}
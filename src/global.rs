@@ -0,0 +1,65 @@
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+const UNINITIALIZED: u8 = 0;
+const INITIALIZING: u8 = 1;
+const INITIALIZED: u8 = 2;
+
+/// A global `&'static T` that can be set once.
+///
+/// A `&'static dyn Trait` is a fat pointer and cannot be stored in an [`AtomicPtr`].
+/// Instead, like [`log::set_logger`], this uses a one-time initialization protocol.
+///
+/// [`AtomicPtr`]: core::sync::atomic::AtomicPtr
+/// [`log::set_logger`]: https://docs.rs/log/latest/log/fn.set_logger.html
+pub(crate) struct Global<T: ?Sized + 'static> {
+    state: AtomicU8,
+    value: UnsafeCell<Option<&'static T>>,
+}
+
+// SAFETY: `value` is only written once, before `state` is set to `INITIALIZED` with `Release` ordering.
+// It is only read after observing `INITIALIZED` with `Acquire` ordering.
+unsafe impl<T: ?Sized + Sync + 'static> Sync for Global<T> {}
+
+impl<T: ?Sized + 'static> Global<T> {
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(UNINITIALIZED),
+            value: UnsafeCell::new(None),
+        }
+    }
+
+    /// Sets the value, returning `false` if it has already been set.
+    pub fn set(&self, value: &'static T) -> bool {
+        if self
+            .state
+            .compare_exchange(
+                UNINITIALIZED,
+                INITIALIZING,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            return false;
+        }
+
+        // SAFETY: We are the only ones who observed `UNINITIALIZED`, and nobody reads before `INITIALIZED`.
+        unsafe {
+            *self.value.get() = Some(value);
+        }
+
+        self.state.store(INITIALIZED, Ordering::Release);
+        true
+    }
+
+    #[inline]
+    pub fn get(&self) -> Option<&'static T> {
+        if self.state.load(Ordering::Acquire) != INITIALIZED {
+            return None;
+        }
+
+        // SAFETY: The value has been initialized and will never be written again.
+        unsafe { *self.value.get() }
+    }
+}
@@ -5,14 +5,93 @@
 //! such as in single-threaded programs that would deadlock on contention.
 //!
 //! See the [`RawOneShotMutex`] and [`RawOneShotRwLock`] types for more information.
+//!
+//! # Owner tracking
+//!
+//! With the `owner-tracking` feature, [`RawOneShotMutex`] records the thread that locked it,
+//! so that contention panics can tell reentrancy from contention with another thread.
+//! The recorded owner is the thread that acquired the lock, not the thread that currently holds the guard.
+//! If a guard is sent to another thread, locking the mutex again on the original thread is still reported as reentrancy,
+//! and locking it on the thread holding the guard is reported as contention with another thread.
+//!
+//! # Model checking
+//!
+//! With the `loom` feature, the lock state uses [`loom`]'s atomic types, so code using these locks can be model checked.
+//! The locks may then only be used inside of [`loom::model`].
+//! Since loom's atomic types cannot be created in const contexts, the lock state is created on first use.
+//! Use each lock once, for example with `is_locked`, before sharing it with other threads.
+//! Otherwise, loom reports a causality violation.
+//!
+//! [`loom`]: https://docs.rs/loom
+//! [`loom::model`]: https://docs.rs/loom/latest/loom/fn.model.html
 
 #![no_std]
 
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+mod abort;
+mod atomic;
+mod borrow;
+mod contention;
+#[cfg(feature = "critical-section")]
+mod cs;
+mod event;
+mod gate;
+mod global;
 mod mutex;
+mod named;
+#[cfg(feature = "poison")]
+mod poison;
+#[cfg(feature = "replay")]
+mod replay;
+#[cfg(feature = "robust")]
+mod robust;
 mod rwlock;
+#[cfg(feature = "std")]
+mod stamped;
+#[cfg(feature = "owner-tracking")]
+mod thread;
 
-pub use mutex::{OneShotMutex, OneShotMutexGuard, RawOneShotMutex};
+pub use abort::{OneShotMutexAbort, OneShotMutexAbortGuard, RawOneShotMutexAbort};
+pub use borrow::BorrowGuard;
+pub use contention::{
+    set_contention_reporter, Contended, ContentionInfo, ContentionReporter,
+    SetContentionReporterError,
+};
+#[cfg(feature = "critical-section")]
+pub use cs::CsOneShotMutex;
+#[cfg(feature = "replay")]
+pub use event::LockEvent;
+pub use gate::OneShotGate;
+#[cfg(feature = "arc_lock")]
+pub use mutex::ArcOneShotMutexGuard;
+pub use mutex::{
+    try_lock_all, try_lock_pair, MappedOneShotMutexGuard, OneShotMutex, OneShotMutexExt,
+    OneShotMutexGuard, RawOneShotMutex,
+};
+pub use named::{NamedOneShotMutex, NamedOneShotRwLock};
+#[cfg(feature = "poison")]
+pub use poison::{PoisonError, PoisonOneShotMutex, PoisonOneShotMutexGuard};
+#[cfg(feature = "replay")]
+pub use replay::{set_replay_sink, ReplayRecord, ReplaySink, ReplayVerifier, SetReplaySinkError};
+#[cfg(feature = "robust")]
+pub use robust::OwnerId;
+#[cfg(feature = "metrics")]
+pub use rwlock::AccessStats;
 pub use rwlock::{
-    OneShotRwLock, OneShotRwLockReadGuard, OneShotRwLockUpgradableReadGuard,
-    OneShotRwLockWriteGuard, RawOneShotRwLock,
+    leak_read, leak_write, Admission, MappedOneShotRwLockReadGuard, MappedOneShotRwLockWriteGuard,
+    OneShotRwLock, OneShotRwLockCapped, OneShotRwLockExt, OneShotRwLockReadGuard,
+    OneShotRwLockUpgradableReadGuard, OneShotRwLockWriteGuard, RawOneShotRwLock,
+    RawOneShotRwLockCapped,
 };
+#[cfg(feature = "arc_lock")]
+pub use rwlock::{
+    ArcOneShotRwLockReadGuard, ArcOneShotRwLockUpgradableReadGuard, ArcOneShotRwLockWriteGuard,
+};
+#[cfg(feature = "std")]
+pub use stamped::StampedGuard;
+
+mod sealed {
+    pub trait Sealed {}
+}
@@ -1,7 +1,37 @@
-use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "track-location")]
+use core::panic::Location;
+#[cfg(feature = "track-location")]
+use core::ptr;
+#[cfg(feature = "track-location")]
+use core::sync::atomic::AtomicPtr;
+#[cfg(feature = "owner-tracking")]
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::Ordering;
+use core::task::Poll;
+use core::{fmt, mem};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
+#[cfg(feature = "std")]
+use lock_api::RawMutexTimed;
 use lock_api::{GuardSend, RawMutex, RawMutexFair};
 
+#[cfg(not(feature = "robust"))]
+use crate::atomic::AtomicBool;
+#[cfg(feature = "robust")]
+use crate::atomic::AtomicU64;
+#[cfg(not(feature = "track-location"))]
+use crate::contention::contended;
+#[cfg(feature = "track-location")]
+use crate::contention::contended_held_at;
+use crate::contention::Contended;
+use crate::event::{emit, emit_failed, LockEvent};
+use crate::sealed::Sealed;
+#[cfg(feature = "robust")]
+use crate::OwnerId;
+#[cfg(feature = "std")]
+use crate::StampedGuard;
+
 /// A one-shot mutex that panics instead of (dead)locking on contention.
 ///
 /// This mutex allows no contention and panics instead of blocking on [`lock`] if it is already locked.
@@ -12,6 +42,25 @@ use lock_api::{GuardSend, RawMutex, RawMutexFair};
 ///
 /// [`lock`]: Self::lock
 ///
+/// # Progress guarantees
+///
+/// [`try_lock_weak`] is wait-free:
+/// it performs at most one load and one compare-and-swap and never loops or spins.
+/// This makes it suitable for real-time code.
+///
+/// The other operations are wait-free on architectures with a compare-and-swap instruction, such as x86-64.
+/// On LL/SC architectures such as AArch64 without LSE, the compare-and-swap of [`try_lock`] and [`lock`] is a loop
+/// that retries if another core interferes between the load and the store.
+///
+/// These guarantees only hold without instrumentation.
+/// The `tracing` and `replay` features call into other code on every operation,
+/// the `owner-tracking` feature initializes a thread-local on the first acquisition in each thread,
+/// and a [`ContentionReporter`] runs on contention.
+///
+/// [`try_lock_weak`]: Self::try_lock_weak
+/// [`try_lock`]: Self::try_lock
+/// [`ContentionReporter`]: crate::ContentionReporter
+///
 /// # Examples
 ///
 /// ```
@@ -29,41 +78,565 @@ use lock_api::{GuardSend, RawMutex, RawMutexFair};
 /// drop(x);
 /// let x = X.lock();
 /// ```
+///
+/// # Standalone use
+///
+/// This mutex can also be used without [`OneShotMutex`] through its inherent methods or the [`RawMutex`] trait,
+/// for example, if the lock state and the protected data live in different places.
+/// The user is then responsible for only accessing the data while holding the lock.
+///
+/// ```
+/// use core::cell::UnsafeCell;
+///
+/// use one_shot_mutex::RawOneShotMutex;
+///
+/// struct Slot {
+///     lock: RawOneShotMutex,
+///     data: UnsafeCell<i32>,
+/// }
+///
+/// // SAFETY: `data` is only accessed while holding `lock`.
+/// unsafe impl Sync for Slot {}
+///
+/// impl Slot {
+///     fn increment(&self) -> i32 {
+///         self.lock.lock();
+///         // SAFETY: We hold the lock.
+///         let value = unsafe {
+///             *self.data.get() += 1;
+///             *self.data.get()
+///         };
+///         // SAFETY: We locked the mutex above.
+///         unsafe { self.lock.unlock() }
+///         value
+///     }
+/// }
+///
+/// let slot = Slot {
+///     lock: RawOneShotMutex::new(),
+///     data: UnsafeCell::new(42),
+/// };
+/// assert_eq!(slot.increment(), 43);
+/// assert!(!slot.lock.is_locked());
+/// ```
 pub struct RawOneShotMutex {
+    #[cfg(not(feature = "robust"))]
     lock: AtomicBool,
+    /// The lock bit and the owning process, see `locked`.
+    #[cfg(feature = "robust")]
+    lock: AtomicU64,
+    #[cfg(feature = "track-location")]
+    location: AtomicPtr<Location<'static>>,
+    #[cfg(feature = "owner-tracking")]
+    thread: AtomicUsize,
+}
+
+/// The value of an unlocked lock word.
+#[cfg(not(feature = "robust"))]
+const UNLOCKED: bool = false;
+
+/// The value of an unlocked lock word.
+#[cfg(feature = "robust")]
+const UNLOCKED: u64 = 0;
+
+/// Returns the value of a lock word that is locked by the current context.
+#[cfg(not(feature = "robust"))]
+#[inline]
+fn locked() -> bool {
+    true
+}
+
+/// Returns the value of a lock word that is locked by the current context.
+///
+/// With the `robust` feature, the lock word also holds the owning process,
+/// so that both are set by the same compare-and-swap.
+/// Otherwise, a process could die after locking but before recording itself as the owner,
+/// which would leave the mutex locked without a way to recover it.
+#[cfg(feature = "robust")]
+#[inline]
+fn locked() -> u64 {
+    1 | OwnerId::current().to_word()
+}
+
+impl RawOneShotMutex {
+    /// Creates a new unlocked mutex.
+    ///
+    /// This is equivalent to [`RawMutex::INIT`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::RawOneShotMutex;
+    ///
+    /// static X: RawOneShotMutex = RawOneShotMutex::new();
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self::INIT
+    }
+
+    /// Acquires this mutex.
+    ///
+    /// This is equivalent to [`RawMutex::lock`] but does not require the trait to be in scope.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the mutex is already locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::RawOneShotMutex;
+    ///
+    /// let mutex = RawOneShotMutex::new();
+    /// mutex.lock();
+    /// assert!(mutex.is_locked());
+    /// assert!(!mutex.try_lock());
+    /// unsafe { mutex.unlock() }
+    /// assert!(!mutex.is_locked());
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn lock(&self) {
+        <Self as RawMutex>::lock(self);
+    }
+
+    /// Attempts to acquire this mutex.
+    ///
+    /// This is equivalent to [`RawMutex::try_lock`] but does not require the trait to be in scope.
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    pub fn try_lock(&self) -> bool {
+        <Self as RawMutex>::try_lock(self)
+    }
+
+    /// Attempts to acquire this mutex, possibly failing spuriously.
+    ///
+    /// Unlike [`try_lock`], this may fail even if the mutex is unlocked.
+    /// On LL/SC architectures such as AArch64 without LSE, this avoids retrying the compare-and-swap,
+    /// which makes it cheaper in hot paths that handle failure like contention anyway.
+    /// On other architectures, this is the same as [`try_lock`].
+    ///
+    /// [`try_lock`]: Self::try_lock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::RawOneShotMutex;
+    ///
+    /// let mutex = RawOneShotMutex::new();
+    ///
+    /// // Spurious failures do not prevent eventual success.
+    /// while !mutex.try_lock_weak() {}
+    /// assert!(!mutex.try_lock_weak());
+    /// unsafe { mutex.unlock() }
+    /// ```
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    pub fn try_lock_weak(&self) -> bool {
+        self.try_acquire(true)
+    }
+
+    /// Unlocks this mutex.
+    ///
+    /// This is equivalent to [`RawMutex::unlock`] but does not require the trait to be in scope.
+    ///
+    /// # Safety
+    ///
+    /// This method may only be called if the mutex is held in the current context, see [`RawMutex::unlock`].
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        unsafe { <Self as RawMutex>::unlock(self) }
+    }
+
+    /// Checks whether this mutex is currently locked.
+    ///
+    /// This is equivalent to [`RawMutex::is_locked`] but does not require the trait to be in scope.
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        <Self as RawMutex>::is_locked(self)
+    }
+
+    /// Returns the location at which this mutex was locked.
+    ///
+    /// Returns `None` if the mutex is not locked.
+    /// This is only meant for diagnostics and may be stale if the mutex is locked or unlocked concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotMutex;
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    /// assert!(unsafe { mutex.raw() }.locked_at().is_none());
+    ///
+    /// let guard = mutex.lock();
+    /// let location = unsafe { mutex.raw() }.locked_at().unwrap();
+    /// assert_eq!(location.line(), line!() - 2);
+    /// ```
+    #[cfg(feature = "track-location")]
+    #[inline]
+    pub fn locked_at(&self) -> Option<&'static Location<'static>> {
+        let location = self.location.load(Ordering::Relaxed);
+        // SAFETY: We only ever store null pointers or pointers derived from `&'static Location<'static>`.
+        unsafe { location.as_ref() }
+    }
+
+    /// Returns the process that owns this mutex.
+    ///
+    /// Returns `None` if the mutex is not locked.
+    /// This may be stale if the mutex is locked or unlocked concurrently.
+    #[cfg(feature = "robust")]
+    #[inline]
+    pub fn owner(&self) -> Option<OwnerId> {
+        OwnerId::from_word(self.lock.load(Ordering::Relaxed))
+    }
+
+    /// Unlocks this mutex if its owner is dead.
+    ///
+    /// If this mutex is locked, this calls `is_owner_alive` with the owning process.
+    /// If that returns `false`, the mutex is forcibly unlocked.
+    /// This allows recovering a mutex in shared memory if the owning process died while holding it, similar to POSIX robust mutexes.
+    ///
+    /// Returns `true` if the mutex was recovered.
+    ///
+    /// Note that the protected data may be in an inconsistent state after recovery,
+    /// since the owner may have died while modifying it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotMutex;
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    /// let guard = mutex.lock();
+    ///
+    /// // Pretend that this process died.
+    /// core::mem::forget(guard);
+    /// assert!(unsafe { mutex.raw() }.recover_if_dead(|_owner| false));
+    ///
+    /// let guard = mutex.lock();
+    /// ```
+    #[cfg(feature = "robust")]
+    pub fn recover_if_dead(&self, is_owner_alive: impl Fn(OwnerId) -> bool) -> bool {
+        let value = self.lock.load(Ordering::Relaxed);
+        let Some(owner) = OwnerId::from_word(value) else {
+            return false;
+        };
+
+        if is_owner_alive(owner) {
+            return false;
+        }
+
+        // Only one recovering process may succeed, and only if the mutex has not been unlocked and relocked in the meantime.
+        // Taking the mutex over before releasing it ensures both.
+        if self
+            .lock
+            .compare_exchange(value, locked(), Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+
+        self.release(Ordering::Release);
+        true
+    }
+
+    /// Returns whether this mutex is locked by the current thread.
+    ///
+    /// This allows distinguishing reentrancy from contention with other threads.
+    ///
+    /// The owner is the thread that acquired the lock, not the thread that currently holds the guard.
+    /// After a guard has been sent to another thread, this still returns `true` on the thread that locked the mutex
+    /// and `false` on the thread that holds the guard.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotMutex;
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    /// let guard = mutex.lock();
+    /// assert!(unsafe { mutex.raw() }.is_locked_by_current_thread());
+    ///
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| assert!(!unsafe { mutex.raw() }.is_locked_by_current_thread()));
+    /// });
+    /// ```
+    #[cfg(feature = "owner-tracking")]
+    #[inline]
+    pub fn is_locked_by_current_thread(&self) -> bool {
+        let thread = self.thread.load(Ordering::Relaxed);
+        thread != 0 && thread == crate::thread::current_id()
+    }
+
+    /// Checks whether this mutex is currently locked with [`Acquire`] ordering.
+    ///
+    /// [`is_locked`] uses [`Relaxed`] ordering, which is sufficient for diagnostics and assertions.
+    /// Use this method instead if the observation is used for synchronization:
+    /// if this returns `false`, all accesses to the protected data by previous holders of the mutex happen before this call.
+    ///
+    /// [`Acquire`]: Ordering::Acquire
+    /// [`is_locked`]: Self::is_locked
+    /// [`Relaxed`]: Ordering::Relaxed
+    #[inline]
+    pub fn is_locked_acquire(&self) -> bool {
+        self.lock.load(Ordering::Acquire) != UNLOCKED
+    }
+
+    /// Forcibly unlocks this mutex.
+    ///
+    /// This resets the mutex to the unlocked state, regardless of whether and by whom it is locked.
+    /// This is useful for recovering a mutex after its guard has been leaked, for example, with [`mem::forget`].
+    ///
+    /// [`mem::forget`]: core::mem::forget
+    ///
+    /// # Safety
+    ///
+    /// No guard of this mutex may be alive, and nobody may access the protected data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotMutex;
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    /// core::mem::forget(mutex.lock());
+    ///
+    /// unsafe { mutex.raw().force_unlock() }
+    /// let guard = mutex.lock();
+    /// ```
+    #[inline]
+    pub unsafe fn force_unlock(&self) {
+        self.release(Ordering::Release);
+    }
+
+    /// Unlocks this mutex with [`SeqCst`] ordering.
+    ///
+    /// [`unlock`] uses [`Release`] ordering, which is all that is needed for protecting data with this mutex.
+    /// `SeqCst` is only needed if this mutex is one component of a lock-free algorithm
+    /// that relies on the single total order of `SeqCst` operations across multiple memory locations.
+    /// For example, in a store-buffering pattern, this thread unlocks and then loads another location with `SeqCst`,
+    /// while another thread stores to that location with `SeqCst`, issues a `SeqCst` fence, and then checks [`is_locked`].
+    /// Only with `SeqCst` unlocking is it impossible for both threads to miss each other's store.
+    ///
+    /// [`SeqCst`]: Ordering::SeqCst
+    /// [`unlock`]: Self::unlock
+    /// [`Release`]: Ordering::Release
+    /// [`is_locked`]: Self::is_locked
+    ///
+    /// # Safety
+    ///
+    /// This method may only be called if the mutex is held in the current context, see [`RawMutex::unlock`].
+    #[inline]
+    pub unsafe fn unlock_seqcst(&self) {
+        debug_assert!(
+            self.is_locked(),
+            "unlock called on an unlocked RawOneShotMutex (double unlock?)"
+        );
+        self.release(Ordering::SeqCst);
+    }
+
+    /// Acquires this mutex without checking for contention in release builds.
+    ///
+    /// With debug assertions, this behaves like [`lock`] and panics on contention.
+    /// Without debug assertions, this marks the mutex as locked with a plain store instead of a compare-and-swap.
+    /// This is meant for hot paths where contention has been ruled out by other means,
+    /// such as in single-threaded programs.
+    ///
+    /// [`lock`]: Self::lock
+    ///
+    /// # Safety
+    ///
+    /// The mutex must be unlocked, and the previous unlock must happen before this call.
+    /// Nobody may try to lock the mutex concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotMutex;
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    ///
+    /// // SAFETY: Nobody else has access to the mutex.
+    /// let guard = unsafe {
+    ///     mutex.raw().lock_unchecked();
+    ///     mutex.make_guard_unchecked()
+    /// };
+    /// assert_eq!(*guard, 42);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub unsafe fn lock_unchecked(&self) {
+        if cfg!(debug_assertions) {
+            if !self.try_lock_strong() {
+                self.contended("lock_unchecked");
+            }
+        } else {
+            self.lock.store(locked(), Ordering::Relaxed);
+            self.acquired();
+        }
+    }
+
+    #[inline]
+    fn release(&self, order: Ordering) {
+        emit(self, LockEvent::Unlock);
+
+        #[cfg(feature = "track-location")]
+        self.location.store(ptr::null_mut(), Ordering::Relaxed);
+
+        #[cfg(feature = "owner-tracking")]
+        self.thread.store(0, Ordering::Relaxed);
+
+        self.lock.store(UNLOCKED, order);
+    }
+
+    /// Attempts to acquire this mutex without checking whether it is locked first.
+    ///
+    /// This is used by locks that panic on contention instead of returning, since they expect to succeed.
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    pub(crate) fn try_lock_strong(&self) -> bool {
+        self.acquire(false)
+    }
+
+    /// Attempts to acquire this mutex if a plain load shows that it is unlocked.
+    ///
+    /// If `weak` is true, this may fail spuriously even if the mutex is unlocked.
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    fn try_acquire(&self, weak: bool) -> bool {
+        // `try_lock` is also polled on mutexes that are mostly locked.
+        // Checking with a plain load first keeps the cache line shared instead of claiming it exclusively for a doomed read-modify-write.
+        if self.is_locked() {
+            emit_failed(self, "try_lock");
+            return false;
+        }
+
+        self.acquire(weak)
+    }
+
+    /// Attempts to acquire this mutex.
+    ///
+    /// If `weak` is true, this may fail spuriously even if the mutex is unlocked.
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    fn acquire(&self, weak: bool) -> bool {
+        // On x86-64, both compile to a single `lock cmpxchg`.
+        // On LL/SC architectures such as AArch64 without LSE, the strong version branches back to `ldaxrb`
+        // if the `stxrb` fails spuriously, while the weak version returns `false` instead.
+        let acquired = if weak {
+            self.lock
+                .compare_exchange_weak(UNLOCKED, locked(), Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        } else {
+            self.lock
+                .compare_exchange(UNLOCKED, locked(), Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        };
+
+        if acquired {
+            self.acquired();
+        } else {
+            emit_failed(self, "try_lock");
+        }
+
+        acquired
+    }
+
+    /// Records that the caller has acquired this mutex.
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    fn acquired(&self) {
+        #[cfg(feature = "track-location")]
+        {
+            let location = ptr::from_ref(Location::caller()).cast_mut();
+            self.location.store(location, Ordering::Relaxed);
+        }
+
+        #[cfg(feature = "owner-tracking")]
+        self.thread
+            .store(crate::thread::current_id(), Ordering::Relaxed);
+
+        emit(self, LockEvent::Lock);
+    }
+
+    /// Reports contention on `operation` and panics.
+    #[cold]
+    #[track_caller]
+    fn contended(&self, operation: &'static str) -> ! {
+        #[cfg(feature = "owner-tracking")]
+        let reason = if self.is_locked_by_current_thread() {
+            "already locked by the current thread (reentrancy)"
+        } else {
+            "already locked by another thread"
+        };
+        #[cfg(not(feature = "owner-tracking"))]
+        let reason = "already locked";
+
+        #[cfg(feature = "track-location")]
+        contended_held_at(operation, "RawOneShotMutex", reason, self.locked_at());
+        #[cfg(not(feature = "track-location"))]
+        contended(operation, "RawOneShotMutex", reason);
+    }
 }
 
 unsafe impl RawMutex for RawOneShotMutex {
     #[allow(clippy::declare_interior_mutable_const)]
     const INIT: Self = Self {
-        lock: AtomicBool::new(false),
+        #[cfg(not(feature = "robust"))]
+        lock: AtomicBool::new(UNLOCKED),
+        #[cfg(feature = "robust")]
+        lock: AtomicU64::new(UNLOCKED),
+        #[cfg(feature = "track-location")]
+        location: AtomicPtr::new(ptr::null_mut()),
+        #[cfg(feature = "owner-tracking")]
+        thread: AtomicUsize::new(0),
     };
 
     type GuardMarker = GuardSend;
 
     #[inline]
+    #[track_caller]
     fn lock(&self) {
-        assert!(
-            self.try_lock(),
-            "called `lock` on a `RawOneShotMutex` that is already locked"
-        );
+        if !self.try_lock_strong() {
+            self.contended("lock");
+        }
     }
 
     #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
     fn try_lock(&self) -> bool {
-        self.lock
-            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
-            .is_ok()
+        self.try_acquire(false)
     }
 
     #[inline]
     unsafe fn unlock(&self) {
-        self.lock.store(false, Ordering::Release);
+        debug_assert!(
+            self.is_locked(),
+            "unlock called on an unlocked RawOneShotMutex (double unlock?)"
+        );
+        self.release(Ordering::Release);
     }
 
     #[inline]
     fn is_locked(&self) -> bool {
-        self.lock.load(Ordering::Relaxed)
+        self.lock.load(Ordering::Relaxed) != UNLOCKED
+    }
+}
+
+impl fmt::Debug for RawOneShotMutex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawOneShotMutex")
+            .field("locked", &self.is_locked())
+            .finish()
+    }
+}
+
+impl Default for RawOneShotMutex {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -77,12 +650,408 @@ unsafe impl RawMutexFair for RawOneShotMutex {
     unsafe fn bump(&self) {}
 }
 
+/// Since this mutex never blocks, any timeout is ignored and the mutex is tried exactly once.
+/// Contention is considered a bug, so waiting for the mutex to become unlocked would not help.
+#[cfg(feature = "std")]
+unsafe impl RawMutexTimed for RawOneShotMutex {
+    type Duration = Duration;
+    type Instant = Instant;
+
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    fn try_lock_for(&self, _timeout: Self::Duration) -> bool {
+        self.try_lock()
+    }
+
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    fn try_lock_until(&self, _timeout: Self::Instant) -> bool {
+        self.try_lock()
+    }
+}
+
 /// A [`lock_api::Mutex`] based on [`RawOneShotMutex`].
+///
+/// Like [`core::sync::Exclusive`], this mutex is [`Sync`] even if `T` is only [`Send`] but not `Sync`,
+/// since all access to `T` is mediated exclusively by the mutex.
+///
+/// ```
+/// use core::cell::Cell;
+///
+/// use one_shot_mutex::OneShotMutex;
+///
+/// static X: OneShotMutex<Cell<i32>> = OneShotMutex::new(Cell::new(42));
+/// ```
+///
+/// `T` still needs to be `Send`, since the data can be accessed from any thread:
+///
+/// ```compile_fail
+/// use std::rc::Rc;
+///
+/// use one_shot_mutex::OneShotMutex;
+///
+/// static X: OneShotMutex<Option<Rc<i32>>> = OneShotMutex::new(None);
+/// ```
+///
+/// [`new`](lock_api::Mutex::new) is a `const fn`, which makes it usable in `static` initializers and `const` blocks.
+/// Outside of const contexts, the mutex can also be created through [`From`]:
+///
+/// ```
+/// use one_shot_mutex::OneShotMutex;
+///
+/// static X: [OneShotMutex<i32>; 2] = [const { OneShotMutex::new(42) }; 2];
+///
+/// let mutex: OneShotMutex<i32> = 42.into();
+/// assert_eq!(*mutex.lock(), *X[0].lock());
+/// ```
+///
+/// [`data_ptr`](lock_api::Mutex::data_ptr) returns a raw pointer to the protected data without locking the mutex,
+/// for example, to hand its stable address to C code or hardware.
+/// The caller is responsible for not accessing the data through the pointer while a guard is alive,
+/// and for not creating guards while the data is accessed through the pointer.
+///
+/// ```
+/// use one_shot_mutex::OneShotMutex;
+///
+/// let mutex = OneShotMutex::new(42);
+/// let ptr = mutex.data_ptr();
+///
+/// // SAFETY: No guard is alive.
+/// unsafe { ptr.write(43) }
+/// assert_eq!(*mutex.lock(), 43);
+/// ```
+///
+/// With the `serde` feature, this mutex implements `Serialize` and `Deserialize`.
+/// Serializing locks the mutex and panics if it is already locked.
+/// Deserializing creates an unlocked mutex.
 pub type OneShotMutex<T> = lock_api::Mutex<RawOneShotMutex, T>;
 
 /// A [`lock_api::MutexGuard`] based on [`RawOneShotMutex`].
+///
+/// The guard can be projected to a part of the protected data with [`map`](lock_api::MutexGuard::map),
+/// which returns a [`MappedOneShotMutexGuard`].
+///
+/// The mutex is unlocked when the guard is dropped.
+/// Ignoring the guard, as in `mutex.lock();`, triggers the `unused_must_use` lint, since the mutex would be unlocked immediately.
+/// `let _ = mutex.lock();` does not trigger the lint but also unlocks the mutex immediately.
+/// Bind the guard to a named variable such as `_guard` instead.
+///
+/// To hold the mutex forever, for example, to take ownership of a hardware register block for the lifetime of the program,
+/// use [`leak`](lock_api::MutexGuard::leak).
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{OneShotMutex, OneShotMutexGuard};
+///
+/// static REGISTERS: OneShotMutex<[u32; 4]> = OneShotMutex::new([0; 4]);
+///
+/// let registers: &'static mut [u32; 4] = OneShotMutexGuard::leak(REGISTERS.lock());
+/// registers[0] = 1;
+/// assert!(REGISTERS.try_lock().is_none());
+/// ```
 pub type OneShotMutexGuard<'a, T> = lock_api::MutexGuard<'a, RawOneShotMutex, T>;
 
+/// A [`lock_api::MappedMutexGuard`] based on [`RawOneShotMutex`].
+///
+/// This is returned by [`map`](lock_api::MutexGuard::map) and [`try_map`](lock_api::MutexGuard::try_map).
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{MappedOneShotMutexGuard, OneShotMutex, OneShotMutexGuard};
+///
+/// let mutex = OneShotMutex::new((1, 2));
+/// let mut second: MappedOneShotMutexGuard<i32> =
+///     OneShotMutexGuard::map(mutex.lock(), |pair| &mut pair.1);
+/// *second += 1;
+/// drop(second);
+///
+/// assert_eq!(*mutex.lock(), (1, 3));
+/// ```
+pub type MappedOneShotMutexGuard<'a, T> = lock_api::MappedMutexGuard<'a, RawOneShotMutex, T>;
+
+/// A [`lock_api::ArcMutexGuard`] based on [`RawOneShotMutex`].
+///
+/// This is returned by [`lock_arc`](lock_api::Mutex::lock_arc) and owns an `Arc` of the mutex instead of borrowing it.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use one_shot_mutex::{ArcOneShotMutexGuard, OneShotMutex};
+///
+/// let mutex = Arc::new(OneShotMutex::new(42));
+/// let guard: ArcOneShotMutexGuard<i32> = mutex.lock_arc();
+/// std::thread::spawn(move || assert_eq!(*guard, 42)).join().unwrap();
+/// ```
+#[cfg(feature = "arc_lock")]
+pub type ArcOneShotMutexGuard<T> = lock_api::ArcMutexGuard<RawOneShotMutex, T>;
+
+/// Extension methods for [`OneShotMutex`].
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait OneShotMutexExt<T: ?Sized>: Sealed {
+    /// Acquires this mutex and records the time of acquisition.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the mutex is already locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotMutex, OneShotMutexExt};
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    /// let guard = mutex.lock_stamped();
+    /// let held_for = guard.acquired_at().elapsed();
+    /// ```
+    #[cfg(feature = "std")]
+    fn lock_stamped(&self) -> StampedGuard<OneShotMutexGuard<'_, T>>;
+
+    /// Attempts to acquire this mutex, returning [`Poll::Pending`] if it is already locked.
+    ///
+    /// This is a minimal bridge to poll-based code.
+    /// It does not register a waker, so the caller is responsible for polling again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::task::Poll;
+    ///
+    /// use one_shot_mutex::{OneShotMutex, OneShotMutexExt};
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    /// let Poll::Ready(guard) = mutex.poll_lock() else {
+    ///     unreachable!();
+    /// };
+    /// assert!(mutex.poll_lock().is_pending());
+    /// ```
+    fn poll_lock(&self) -> Poll<OneShotMutexGuard<'_, T>>;
+
+    /// Attempts to acquire this mutex, returning [`Contended`] if it is already locked.
+    ///
+    /// This is like [`try_lock`], but allows propagating contention with `?`.
+    ///
+    /// [`try_lock`]: lock_api::Mutex::try_lock
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the mutex is already locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{Contended, OneShotMutex, OneShotMutexExt};
+    ///
+    /// fn increment(mutex: &OneShotMutex<i32>) -> Result<(), Contended> {
+    ///     *mutex.checked_lock()? += 1;
+    ///     Ok(())
+    /// }
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    /// increment(&mutex).unwrap();
+    ///
+    /// let guard = mutex.lock();
+    /// assert!(increment(&mutex).is_err());
+    /// ```
+    fn checked_lock(&self) -> Result<OneShotMutexGuard<'_, T>, Contended>;
+
+    /// Acquires this mutex, calls `f` with the protected data, and releases the mutex.
+    ///
+    /// This keeps the lock held only for the duration of `f`,
+    /// which prevents accidentally holding the guard for longer and causing contention later.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the mutex is already locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotMutex, OneShotMutexExt};
+    ///
+    /// let mutex = OneShotMutex::new(42);
+    /// let x = mutex.with(|x| {
+    ///     *x += 1;
+    ///     *x
+    /// });
+    /// assert_eq!(x, 43);
+    /// assert!(!mutex.is_locked());
+    /// ```
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R;
+
+    /// Replaces the protected data, returning the old value.
+    ///
+    /// Since this call borrows the mutex mutably, no locking is needed.
+    /// This allows recycling a mutex, for example, in an object pool.
+    ///
+    /// In debug builds, this asserts that the mutex is unlocked, which only fails if a guard has been leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotMutex, OneShotMutexExt};
+    ///
+    /// let mut mutex = OneShotMutex::new(vec![1, 2, 3]);
+    /// let old = mutex.replace(Vec::new());
+    /// assert_eq!(old, [1, 2, 3]);
+    /// assert!(mutex.lock().is_empty());
+    /// ```
+    fn replace(&mut self, value: T) -> T
+    where
+        T: Sized;
+
+    /// Resets this mutex to a new unlocked mutex protecting `value`.
+    ///
+    /// Since this call borrows the mutex mutably, no locking is needed.
+    /// This allows reinitializing a table of mutexes in place, for example, after a soft reset.
+    ///
+    /// In debug builds, this asserts that the mutex is unlocked, which only fails if a guard has been leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotMutex, OneShotMutexExt};
+    ///
+    /// let mut table = [const { OneShotMutex::new(0) }; 4];
+    /// *table[1].lock() = 42;
+    ///
+    /// for mutex in &mut table {
+    ///     mutex.reset(0);
+    /// }
+    /// assert_eq!(*table[1].lock(), 0);
+    /// ```
+    fn reset(&mut self, value: T)
+    where
+        T: Sized;
+}
+
+impl<T: ?Sized> Sealed for OneShotMutex<T> {}
+
+impl<T: ?Sized> OneShotMutexExt<T> for OneShotMutex<T> {
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn lock_stamped(&self) -> StampedGuard<OneShotMutexGuard<'_, T>> {
+        StampedGuard::new(self.lock())
+    }
+
+    #[inline]
+    fn poll_lock(&self) -> Poll<OneShotMutexGuard<'_, T>> {
+        match self.try_lock() {
+            Some(guard) => Poll::Ready(guard),
+            None => Poll::Pending,
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+    fn checked_lock(&self) -> Result<OneShotMutexGuard<'_, T>, Contended> {
+        self.try_lock().ok_or(Contended::new())
+    }
+
+    #[inline]
+    #[track_caller]
+    fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    #[inline]
+    fn replace(&mut self, value: T) -> T
+    where
+        T: Sized,
+    {
+        debug_assert!(!self.is_locked(), "replace a locked mutex");
+        mem::replace(self.get_mut(), value)
+    }
+
+    #[inline]
+    fn reset(&mut self, value: T)
+    where
+        T: Sized,
+    {
+        debug_assert!(!self.is_locked(), "reset a locked mutex");
+        *self = Self::new(value);
+    }
+}
+
+/// Attempts to acquire two mutexes together.
+///
+/// This locks `a` and then tries to lock `b`.
+/// If either mutex is already locked, no mutex stays locked and `None` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{try_lock_pair, OneShotMutex};
+///
+/// let a = OneShotMutex::new(1);
+/// let b = OneShotMutex::new(2);
+///
+/// let guard = b.lock();
+/// assert!(try_lock_pair(&a, &b).is_none());
+/// assert!(!a.is_locked());
+/// drop(guard);
+///
+/// let (a, b) = try_lock_pair(&a, &b).unwrap();
+/// assert_eq!((*a, *b), (1, 2));
+/// ```
+#[inline]
+#[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+pub fn try_lock_pair<'a, A: ?Sized, B: ?Sized>(
+    a: &'a OneShotMutex<A>,
+    b: &'a OneShotMutex<B>,
+) -> Option<(OneShotMutexGuard<'a, A>, OneShotMutexGuard<'a, B>)> {
+    let a = a.try_lock()?;
+    // If `b` is contended, dropping `a` releases it again.
+    let b = b.try_lock()?;
+    Some((a, b))
+}
+
+/// Attempts to acquire all mutexes together.
+///
+/// This locks the mutexes in order.
+/// If any mutex is already locked, all previously acquired mutexes are released in reverse order and `None` is returned.
+/// Passing the same mutex twice always fails.
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{try_lock_all, OneShotMutex};
+///
+/// let mutexes = [OneShotMutex::new(1), OneShotMutex::new(2), OneShotMutex::new(3)];
+/// let [a, b, c] = &mutexes;
+///
+/// let guard = c.lock();
+/// assert!(try_lock_all(&[a, b, c]).is_none());
+/// assert!(!a.is_locked() && !b.is_locked());
+/// drop(guard);
+///
+/// let guards = try_lock_all(&[a, b, c]).unwrap();
+/// assert_eq!(guards.map(|guard| *guard), [1, 2, 3]);
+/// ```
+#[cfg_attr(any(feature = "track-location", feature = "tracing"), track_caller)]
+pub fn try_lock_all<'a, T: ?Sized, const N: usize>(
+    mutexes: &[&'a OneShotMutex<T>; N],
+) -> Option<[OneShotMutexGuard<'a, T>; N]> {
+    for (i, mutex) in mutexes.iter().enumerate() {
+        // SAFETY: We only unlock the mutexes that we have locked below.
+        if !unsafe { mutex.raw() }.try_lock() {
+            for mutex in mutexes[..i].iter().rev() {
+                // SAFETY: We have locked this mutex above, and no guard for it exists yet.
+                unsafe { mutex.force_unlock() }
+            }
+            return None;
+        }
+    }
+
+    // SAFETY: We have locked all mutexes above.
+    Some(mutexes.map(|mutex| unsafe { mutex.make_guard_unchecked() }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -107,6 +1076,18 @@ mod tests {
         let _guard2 = mutex.lock();
     }
 
+    #[test]
+    #[cfg(feature = "arc_lock")]
+    #[should_panic]
+    fn lock_arc_panic() {
+        use std::sync::Arc;
+
+        let mutex = Arc::new(OneShotMutex::new(42));
+        let guard: ArcOneShotMutexGuard<i32> = mutex.lock_arc();
+        assert_eq!(*guard, 42);
+        let _guard2 = mutex.lock_arc();
+    }
+
     #[test]
     fn try_lock() {
         let mutex = OneShotMutex::new(42);
@@ -119,4 +1100,498 @@ mod tests {
         let guard = mutex.try_lock().unwrap();
         assert_eq!(*guard, 43);
     }
+
+    #[test]
+    fn construct() {
+        static STATIC: OneShotMutex<i32> = OneShotMutex::new(42);
+        static ARRAY: [OneShotMutex<i32>; 2] = [const { OneShotMutex::new(42) }; 2];
+
+        let from: OneShotMutex<i32> = 42.into();
+        assert_eq!(*STATIC.lock(), 42);
+        assert_eq!(*ARRAY[1].lock(), 42);
+        assert_eq!(*from.lock(), 42);
+    }
+
+    #[test]
+    fn lock_unchecked() {
+        let mutex = OneShotMutex::new(42);
+        let guard = unsafe {
+            mutex.raw().lock_unchecked();
+            mutex.make_guard_unchecked()
+        };
+        assert_eq!(*guard, 42);
+        assert!(mutex.is_locked());
+        drop(guard);
+        assert!(!mutex.is_locked());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "called `lock_unchecked` on a `RawOneShotMutex` that is already locked"]
+    fn lock_unchecked_panic() {
+        let mutex = OneShotMutex::new(42);
+        let _guard = mutex.lock();
+        unsafe { mutex.raw().lock_unchecked() }
+    }
+
+    #[test]
+    fn raw() {
+        let mutex = RawOneShotMutex::new();
+        let mut data = 42;
+
+        mutex.lock();
+        assert!(mutex.is_locked());
+        assert!(!mutex.try_lock_strong());
+        data += 1;
+        unsafe { mutex.unlock() }
+        assert!(!mutex.is_locked());
+
+        assert!(mutex.try_lock_strong());
+        data += 1;
+        unsafe { mutex.unlock() }
+        assert_eq!(data, 44);
+    }
+
+    #[test]
+    fn try_lock_pair() {
+        let a = OneShotMutex::new(1);
+        let b = OneShotMutex::new(2);
+
+        let (guard_a, guard_b) = super::try_lock_pair(&a, &b).unwrap();
+        assert_eq!((*guard_a, *guard_b), (1, 2));
+        assert!(super::try_lock_pair(&a, &b).is_none());
+        drop((guard_a, guard_b));
+
+        // The first mutex is contended.
+        let guard = a.lock();
+        assert!(super::try_lock_pair(&a, &b).is_none());
+        assert!(!b.is_locked());
+        drop(guard);
+
+        // The second mutex is contended.
+        let guard = b.lock();
+        assert!(super::try_lock_pair(&a, &b).is_none());
+        assert!(!a.is_locked());
+        drop(guard);
+
+        // The same mutex twice.
+        assert!(super::try_lock_pair(&a, &a).is_none());
+        assert!(!a.is_locked());
+    }
+
+    #[test]
+    fn try_lock_all() {
+        let mutexes = [
+            OneShotMutex::new(0),
+            OneShotMutex::new(1),
+            OneShotMutex::new(2),
+            OneShotMutex::new(3),
+        ];
+        let refs = [&mutexes[0], &mutexes[1], &mutexes[2], &mutexes[3]];
+
+        let guards = super::try_lock_all(&refs).unwrap();
+        assert_eq!(guards.each_ref().map(|guard| **guard), [0, 1, 2, 3]);
+        assert!(super::try_lock_all(&refs).is_none());
+        drop(guards);
+
+        // Each mutex is contended in turn.
+        for i in 0..mutexes.len() {
+            let guard = mutexes[i].lock();
+            assert!(super::try_lock_all(&refs).is_none());
+            for (j, mutex) in mutexes.iter().enumerate() {
+                assert_eq!(mutex.is_locked(), i == j);
+            }
+            drop(guard);
+        }
+
+        // The same mutex twice.
+        assert!(super::try_lock_all(&[&mutexes[0], &mutexes[1], &mutexes[0]]).is_none());
+        assert!(mutexes.iter().all(|mutex| !mutex.is_locked()));
+
+        let empty: [&OneShotMutex<i32>; 0] = [];
+        assert!(super::try_lock_all(&empty).is_some());
+    }
+
+    #[test]
+    fn reset() {
+        let mut mutex = OneShotMutex::new(42);
+        *mutex.lock() += 1;
+        mutex.reset(0);
+        assert!(!mutex.is_locked());
+        assert_eq!(*mutex.lock(), 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "reset a locked mutex"]
+    fn reset_locked_panic() {
+        let mut mutex = OneShotMutex::new(42);
+        mem::forget(mutex.lock());
+        mutex.reset(0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "replace a locked mutex"]
+    fn replace_locked_panic() {
+        let mut mutex = OneShotMutex::new(42);
+        mem::forget(mutex.lock());
+        mutex.replace(0);
+    }
+
+    #[test]
+    fn data_ptr() {
+        let mutex = OneShotMutex::new(42);
+        let ptr = mutex.data_ptr();
+        assert_eq!(unsafe { ptr.read() }, 42);
+        assert!(!mutex.is_locked());
+
+        unsafe { ptr.write(43) }
+        let mut guard = mutex.lock();
+        assert_eq!(ptr.cast_const(), &raw const *guard);
+        *guard += 1;
+        drop(guard);
+        assert_eq!(unsafe { ptr.read() }, 44);
+    }
+
+    #[test]
+    fn leak() {
+        static MUTEX: OneShotMutex<i32> = OneShotMutex::new(42);
+
+        let x: &'static mut i32 = OneShotMutexGuard::leak(MUTEX.lock());
+        *x += 1;
+        assert!(MUTEX.is_locked());
+        assert!(MUTEX.try_lock().is_none());
+        assert_eq!(*x, 43);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "unlock called on an unlocked RawOneShotMutex (double unlock?)"]
+    fn double_unlock_panic() {
+        let mutex = RawOneShotMutex::new();
+        mutex.lock();
+        unsafe {
+            mutex.unlock();
+            mutex.unlock();
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "unlock called on an unlocked RawOneShotMutex (double unlock?)"]
+    fn unlock_seqcst_unlocked_panic() {
+        let mutex = RawOneShotMutex::new();
+        unsafe { mutex.unlock_seqcst() }
+    }
+
+    #[test]
+    fn map() {
+        let mutex = OneShotMutex::new((1u8, 2u8));
+        let mut guard: MappedOneShotMutexGuard<u8> =
+            OneShotMutexGuard::map(mutex.lock(), |pair| &mut pair.0);
+        assert_eq!(*guard, 1);
+        *guard += 1;
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+
+        let guard = OneShotMutexGuard::try_map(mutex.lock(), |_| None::<&mut u8>).unwrap_err();
+        assert_eq!(*guard, (2, 2));
+    }
+
+    #[test]
+    fn try_lock_weak() {
+        let mutex = RawOneShotMutex::new();
+
+        // Spurious failures must not prevent eventual success on a single thread.
+        while !mutex.try_lock_weak() {}
+        // Nothing unlocks the mutex, so this only returns if it gives up after one attempt.
+        assert!(!mutex.try_lock_weak());
+        assert!(!mutex.try_lock());
+
+        unsafe { mutex.unlock() }
+        assert!(!mutex.is_locked());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn lock_stamped() {
+        use std::time::Instant;
+
+        let mutex = OneShotMutex::new(42);
+        let before = Instant::now();
+        let guard = mutex.lock_stamped();
+        assert_eq!(*guard, 42);
+        assert!(before <= guard.acquired_at());
+        assert!(guard.acquired_at() <= Instant::now());
+        assert!(mutex.try_lock().is_none());
+
+        let acquired_at = guard.acquired_at();
+        drop(guard);
+        let guard = mutex.lock_stamped();
+        assert!(acquired_at <= guard.acquired_at());
+    }
+
+    #[test]
+    fn debug() {
+        use std::format;
+
+        let mutex = OneShotMutex::new(42);
+        assert_eq!(
+            format!("{:?}", unsafe { mutex.raw() }),
+            "RawOneShotMutex { locked: false }"
+        );
+
+        let _guard = mutex.lock();
+        assert_eq!(
+            format!("{:?}", unsafe { mutex.raw() }),
+            "RawOneShotMutex { locked: true }"
+        );
+    }
+
+    #[test]
+    fn default() {
+        use std::vec::Vec;
+
+        #[derive(Default)]
+        struct State {
+            buf: OneShotMutex<Vec<u8>>,
+        }
+
+        let mutex = OneShotMutex::<Vec<u8>>::default();
+        assert!(!mutex.is_locked());
+        assert_eq!(*mutex.lock(), Vec::<u8>::default());
+
+        let state = State::default();
+        assert!(state.buf.lock().is_empty());
+    }
+
+    #[test]
+    fn sync() {
+        use core::cell::Cell;
+
+        fn assert_sync<T: Sync>() {}
+
+        assert_sync::<OneShotMutex<Cell<i32>>>();
+    }
+
+    #[test]
+    fn poll_lock() {
+        let mutex = OneShotMutex::new(42);
+        let Poll::Ready(mut guard) = mutex.poll_lock() else {
+            panic!("mutex should be unlocked");
+        };
+        assert_eq!(*guard, 42);
+        assert!(mutex.poll_lock().is_pending());
+
+        *guard += 1;
+        drop(guard);
+        let Poll::Ready(guard) = mutex.poll_lock() else {
+            panic!("mutex should be unlocked");
+        };
+        assert_eq!(*guard, 43);
+    }
+
+    #[test]
+    fn checked_lock() {
+        let mutex = OneShotMutex::new(42);
+        let mut guard = mutex.checked_lock().unwrap();
+        assert_eq!(*guard, 42);
+        assert_eq!(mutex.checked_lock().unwrap_err(), Contended::new());
+
+        *guard += 1;
+        drop(guard);
+        assert_eq!(*mutex.checked_lock().unwrap(), 43);
+    }
+
+    #[test]
+    fn with() {
+        let mutex = OneShotMutex::new(42);
+        mutex.with(|x| *x += 1);
+        let guard = mutex.lock();
+        assert_eq!(*guard, 43);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn try_lock_for() {
+        let mutex = OneShotMutex::new(42);
+        let guard = mutex.try_lock_for(Duration::from_secs(1)).unwrap();
+        assert!(mutex.try_lock_for(Duration::ZERO).is_none());
+        assert!(mutex.try_lock_until(Instant::now()).is_none());
+        drop(guard);
+
+        let guard = mutex.try_lock_until(Instant::now()).unwrap();
+        assert!(mutex.try_lock_for(Duration::MAX).is_none());
+        drop(guard);
+    }
+
+    #[test]
+    fn replace() {
+        let mut mutex = OneShotMutex::new(42);
+        assert_eq!(mutex.replace(43), 42);
+        assert!(!mutex.is_locked());
+        assert_eq!(*mutex.lock(), 43);
+    }
+
+    #[test]
+    fn unlock_seqcst() {
+        let mutex = OneShotMutex::new(42);
+        let guard = mutex.lock();
+        core::mem::forget(guard);
+        assert!(mutex.is_locked());
+
+        unsafe {
+            mutex.raw().unlock_seqcst();
+        }
+        assert!(!mutex.is_locked());
+        assert!(mutex.try_lock().is_some());
+    }
+
+    #[test]
+    fn is_locked_acquire() {
+        let mutex = RawOneShotMutex::INIT;
+        assert!(!mutex.is_locked_acquire());
+
+        mutex.lock();
+        assert!(mutex.is_locked_acquire());
+
+        unsafe { mutex.unlock() }
+        assert!(!mutex.is_locked_acquire());
+    }
+
+    #[test]
+    fn force_unlock() {
+        let mutex = RawOneShotMutex::INIT;
+        unsafe { mutex.force_unlock() }
+        assert!(!mutex.is_locked());
+
+        mutex.lock();
+        unsafe { mutex.force_unlock() }
+        assert!(!mutex.is_locked());
+        assert!(mutex.try_lock());
+    }
+
+    #[test]
+    #[cfg(feature = "track-location")]
+    fn locked_at() {
+        let mutex = RawOneShotMutex::INIT;
+        assert!(mutex.locked_at().is_none());
+
+        let line = line!() + 1;
+        mutex.lock();
+        let location = mutex.locked_at().unwrap();
+        assert_eq!(location.file(), file!());
+        assert_eq!(location.line(), line);
+
+        unsafe {
+            mutex.unlock();
+        }
+        assert!(mutex.locked_at().is_none());
+
+        let line = line!() + 1;
+        assert!(mutex.try_lock());
+        assert_eq!(mutex.locked_at().unwrap().line(), line);
+    }
+
+    #[test]
+    #[cfg(feature = "owner-tracking")]
+    fn is_locked_by_current_thread() {
+        let mutex = RawOneShotMutex::INIT;
+        assert!(!mutex.is_locked_by_current_thread());
+
+        mutex.lock();
+        assert!(mutex.is_locked_by_current_thread());
+        std::thread::scope(|s| {
+            s.spawn(|| assert!(!mutex.is_locked_by_current_thread()));
+        });
+
+        unsafe { mutex.unlock() }
+        assert!(!mutex.is_locked_by_current_thread());
+    }
+
+    #[test]
+    #[cfg(feature = "owner-tracking")]
+    #[should_panic = "already locked by the current thread (reentrancy)"]
+    fn lock_reentrancy_panic() {
+        let mutex = RawOneShotMutex::INIT;
+        mutex.lock();
+        mutex.lock();
+    }
+
+    #[test]
+    #[cfg(all(feature = "owner-tracking", panic = "unwind"))]
+    fn lock_other_thread_panic() {
+        let mutex = RawOneShotMutex::INIT;
+        mutex.lock();
+
+        let payload = std::thread::scope(|s| s.spawn(|| mutex.lock()).join().unwrap_err());
+        let message = payload.downcast::<std::string::String>().unwrap();
+        assert!(message.contains("already locked by another thread"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "owner-tracking", panic = "unwind"))]
+    fn lock_sent_guard_panic() {
+        use std::panic::{self, AssertUnwindSafe};
+        use std::string::String;
+        use std::sync::Barrier;
+
+        fn lock_panic_message(mutex: &RawOneShotMutex) -> String {
+            let payload = panic::catch_unwind(AssertUnwindSafe(|| mutex.lock())).unwrap_err();
+            *payload.downcast::<String>().unwrap()
+        }
+
+        let mutex = OneShotMutex::new(42);
+        let raw = unsafe { mutex.raw() };
+        let guard = mutex.lock();
+        let barrier = &Barrier::new(2);
+
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                let _guard = guard;
+                // The owner stays the locking thread, even though this thread holds the guard.
+                assert!(!raw.is_locked_by_current_thread());
+                assert!(lock_panic_message(raw).contains("already locked by another thread"));
+                barrier.wait();
+                // Keep holding the guard until the locking thread has checked.
+                barrier.wait();
+            });
+
+            barrier.wait();
+            assert!(raw.is_locked_by_current_thread());
+            assert!(lock_panic_message(raw)
+                .contains("already locked by the current thread (reentrancy)"));
+            barrier.wait();
+        });
+
+        assert!(!mutex.is_locked());
+    }
+
+    #[test]
+    #[cfg(feature = "robust")]
+    fn recover_if_dead() {
+        let mutex = RawOneShotMutex::INIT;
+        assert!(mutex.owner().is_none());
+        assert!(!mutex.recover_if_dead(|_| false));
+
+        mutex.lock();
+        assert_eq!(mutex.owner(), Some(OwnerId::current()));
+        assert!(!mutex.recover_if_dead(|_| true));
+        assert!(mutex.is_locked());
+
+        assert!(mutex.recover_if_dead(|owner| owner != OwnerId::current()));
+        assert!(!mutex.is_locked());
+        assert!(mutex.owner().is_none());
+
+        assert!(mutex.try_lock());
+
+        // A mutex that is unlocked while checking its owner must not be unlocked again.
+        let recovered = mutex.recover_if_dead(|_| {
+            unsafe { mutex.unlock() }
+            false
+        });
+        assert!(!recovered);
+        assert!(!mutex.is_locked());
+    }
 }
@@ -0,0 +1,288 @@
+use core::any::type_name;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use crate::contention::contended_typed;
+use crate::{
+    OneShotMutex, OneShotMutexGuard, OneShotRwLock, OneShotRwLockReadGuard,
+    OneShotRwLockUpgradableReadGuard, OneShotRwLockWriteGuard,
+};
+
+/// A [`OneShotMutex`] that names the protected type in contention panics.
+///
+/// The raw mutex does not know the protected type, so its panic message only names [`RawOneShotMutex`].
+/// This wrapper overrides [`lock`] to include [`type_name`] in the panic message instead,
+/// such as ``called `lock` on a `OneShotMutex<DeviceState>` that is already locked``.
+/// All other methods are available through [`Deref`].
+///
+/// [`RawOneShotMutex`]: crate::RawOneShotMutex
+/// [`lock`]: Self::lock
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::NamedOneShotMutex;
+///
+/// static X: NamedOneShotMutex<i32> = NamedOneShotMutex::new(42);
+///
+/// let x = X.lock();
+///
+/// // This panics with "called `lock` on a `OneShotMutex<i32>` that is already locked".
+/// // let x2 = X.lock();
+/// ```
+#[derive(Default)]
+pub struct NamedOneShotMutex<T: ?Sized>(OneShotMutex<T>);
+
+impl<T> NamedOneShotMutex<T> {
+    /// Creates a new mutex in an unlocked state ready for use.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self(OneShotMutex::new(value))
+    }
+
+    /// Consumes this mutex, returning the underlying data.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T: ?Sized> NamedOneShotMutex<T> {
+    /// Acquires this mutex.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the mutex is already locked.
+    #[inline]
+    #[track_caller]
+    pub fn lock(&self) -> OneShotMutexGuard<'_, T> {
+        // SAFETY: We do not unlock the mutex.
+        if unsafe { self.0.raw() }.try_lock_strong() {
+            // SAFETY: We just locked the mutex.
+            unsafe { self.0.make_guard_unchecked() }
+        } else {
+            contended_typed("lock", "OneShotMutex", type_name::<T>(), "already locked")
+        }
+    }
+}
+
+impl<T> From<T> for NamedOneShotMutex<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized> Deref for NamedOneShotMutex<T> {
+    type Target = OneShotMutex<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for NamedOneShotMutex<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for NamedOneShotMutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A [`OneShotRwLock`] that names the protected type in contention panics.
+///
+/// Like [`NamedOneShotMutex`], this overrides [`read`], [`write`], and [`upgradable_read`] to include [`type_name`] in the panic message.
+/// All other methods are available through [`Deref`].
+///
+/// [`read`]: Self::read
+/// [`write`]: Self::write
+/// [`upgradable_read`]: Self::upgradable_read
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::NamedOneShotRwLock;
+///
+/// static X: NamedOneShotRwLock<i32> = NamedOneShotRwLock::new(42);
+///
+/// let x = X.write();
+///
+/// // This panics with "called `read` on a `OneShotRwLock<i32>` that is already locked exclusively".
+/// // let x2 = X.read();
+/// ```
+#[derive(Default)]
+pub struct NamedOneShotRwLock<T: ?Sized>(OneShotRwLock<T>);
+
+impl<T> NamedOneShotRwLock<T> {
+    /// Creates a new lock in an unlocked state ready for use.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self(OneShotRwLock::new(value))
+    }
+
+    /// Consumes this lock, returning the underlying data.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<T: ?Sized> NamedOneShotRwLock<T> {
+    /// Locks this lock with shared read access.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lock is already locked exclusively.
+    #[inline]
+    #[track_caller]
+    pub fn read(&self) -> OneShotRwLockReadGuard<'_, T> {
+        match self.0.try_read() {
+            Some(guard) => guard,
+            None => contended_typed(
+                "read",
+                "OneShotRwLock",
+                type_name::<T>(),
+                "already locked exclusively",
+            ),
+        }
+    }
+
+    /// Locks this lock with exclusive write access.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lock is already locked.
+    #[inline]
+    #[track_caller]
+    pub fn write(&self) -> OneShotRwLockWriteGuard<'_, T> {
+        // SAFETY: We do not unlock the lock.
+        if unsafe { self.0.raw() }.try_lock_exclusive_strong() {
+            // SAFETY: We just locked the lock exclusively.
+            unsafe { self.0.make_write_guard_unchecked() }
+        } else {
+            contended_typed("write", "OneShotRwLock", type_name::<T>(), "already locked")
+        }
+    }
+
+    /// Locks this lock with upgradable read access.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lock is already locked upgradably or exclusively.
+    #[inline]
+    #[track_caller]
+    pub fn upgradable_read(&self) -> OneShotRwLockUpgradableReadGuard<'_, T> {
+        // SAFETY: We do not unlock the lock.
+        match unsafe { self.0.raw() }.try_lock_upgradable_with_reason() {
+            // SAFETY: We just locked the lock upgradably.
+            Ok(()) => unsafe { self.0.make_upgradable_guard_unchecked() },
+            Err(reason) => {
+                contended_typed("upgradable_read", "OneShotRwLock", type_name::<T>(), reason)
+            }
+        }
+    }
+}
+
+impl<T> From<T> for NamedOneShotRwLock<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized> Deref for NamedOneShotRwLock<T> {
+    type Target = OneShotRwLock<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for NamedOneShotRwLock<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for NamedOneShotRwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Default)]
+    struct DeviceState(u8);
+
+    #[test]
+    fn lock() {
+        let mutex = NamedOneShotMutex::new(42);
+        let mut guard = mutex.lock();
+        *guard += 1;
+        assert!(mutex.try_lock().is_none());
+        drop(guard);
+        assert_eq!(*mutex.lock(), 43);
+    }
+
+    #[test]
+    #[should_panic = "called `lock` on a `OneShotMutex<one_shot_mutex::named::tests::DeviceState>` that is already locked"]
+    fn lock_panic() {
+        let mutex = NamedOneShotMutex::<DeviceState>::default();
+        let _guard = mutex.lock();
+        let _guard2 = mutex.lock();
+    }
+
+    #[test]
+    fn rwlock() {
+        let lock = NamedOneShotRwLock::new(42);
+        *lock.write() += 1;
+        let guard = lock.read();
+        let guard2 = lock.upgradable_read();
+        assert_eq!(*guard + *guard2, 86);
+        assert!(lock.try_write().is_none());
+    }
+
+    #[test]
+    #[should_panic = "called `read` on a `OneShotRwLock<one_shot_mutex::named::tests::DeviceState>` that is already locked exclusively"]
+    fn read_panic() {
+        let lock = NamedOneShotRwLock::<DeviceState>::default();
+        let _guard = lock.write();
+        let _guard2 = lock.read();
+    }
+
+    #[test]
+    #[should_panic = "called `write` on a `OneShotRwLock<[u8]>` that is already locked"]
+    fn write_panic() {
+        let lock: &NamedOneShotRwLock<[u8]> = &NamedOneShotRwLock::new([1, 2, 3]);
+        let _guard = lock.read();
+        let _guard2 = lock.write();
+    }
+
+    #[test]
+    #[should_panic = "called `upgradable_read` on a `OneShotRwLock<i32>` that is already locked upgradably (is the upgradable guard held recursively?)"]
+    fn upgradable_read_panic() {
+        let lock = NamedOneShotRwLock::new(42);
+        let _guard = lock.upgradable_read();
+        let _guard2 = lock.upgradable_read();
+    }
+
+    #[test]
+    #[should_panic = "called `upgradable_read` on a `OneShotRwLock<i32>` that is already locked exclusively"]
+    fn upgradable_read_exclusive_panic() {
+        let lock = NamedOneShotRwLock::new(42);
+        let _guard = lock.write();
+        let _guard2 = lock.upgradable_read();
+    }
+}
@@ -0,0 +1,307 @@
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{OneShotMutex, OneShotMutexGuard};
+
+/// A [`OneShotMutex`] that is poisoned if a guard is dropped during a panic.
+///
+/// This mirrors the poisoning of [`std::sync::Mutex`].
+/// If a panic unwinds while a guard is held, the critical section may have been interrupted,
+/// and subsequent calls to [`lock`] return a [`PoisonError`].
+/// Poisoning does not prevent access to the data, see [`PoisonError::into_inner`].
+///
+/// Detecting panics requires the `std` feature.
+/// Without it, this mutex is never poisoned.
+///
+/// A contention panic in [`lock`] does not poison this mutex, since no guard has been acquired.
+/// On the other hand, if a guard of this mutex is held while a contention panic of any lock unwinds, this mutex is poisoned.
+///
+/// [`std::sync::Mutex`]: https://doc.rust-lang.org/std/sync/struct.Mutex.html
+/// [`lock`]: Self::lock
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::PoisonOneShotMutex;
+///
+/// static X: PoisonOneShotMutex<i32> = PoisonOneShotMutex::new(42);
+///
+/// let mut x = X.lock().unwrap();
+/// *x += 1;
+/// ```
+pub struct PoisonOneShotMutex<T: ?Sized> {
+    poisoned: AtomicBool,
+    inner: OneShotMutex<T>,
+}
+
+impl<T> PoisonOneShotMutex<T> {
+    /// Creates a new mutex in an unlocked and unpoisoned state ready for use.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self {
+            poisoned: AtomicBool::new(false),
+            inner: OneShotMutex::new(value),
+        }
+    }
+
+    /// Consumes this mutex, returning the underlying data.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with the underlying data if the mutex is poisoned.
+    #[inline]
+    pub fn into_inner(self) -> Result<T, PoisonError<T>> {
+        let poisoned = self.is_poisoned();
+        let value = self.inner.into_inner();
+        if poisoned {
+            Err(PoisonError::new(value))
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+impl<T: ?Sized> PoisonOneShotMutex<T> {
+    /// Acquires this mutex.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with the acquired guard if the mutex is poisoned.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the mutex is already locked.
+    #[inline]
+    #[track_caller]
+    pub fn lock(
+        &self,
+    ) -> Result<PoisonOneShotMutexGuard<'_, T>, PoisonError<PoisonOneShotMutexGuard<'_, T>>> {
+        self.wrap(self.inner.lock())
+    }
+
+    /// Attempts to acquire this mutex.
+    ///
+    /// Returns `None` if the mutex is already locked.
+    /// Otherwise, this fails with the acquired guard if the mutex is poisoned.
+    #[inline]
+    pub fn try_lock(
+        &self,
+    ) -> Option<Result<PoisonOneShotMutexGuard<'_, T>, PoisonError<PoisonOneShotMutexGuard<'_, T>>>>
+    {
+        self.inner.try_lock().map(|guard| self.wrap(guard))
+    }
+
+    /// Returns whether this mutex is poisoned.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Relaxed)
+    }
+
+    /// Clears the poisoned state of this mutex.
+    ///
+    /// This allows recovering from a panic once the data has been restored to a consistent state.
+    #[inline]
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Relaxed);
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with the mutable reference if the mutex is poisoned.
+    #[inline]
+    pub fn get_mut(&mut self) -> Result<&mut T, PoisonError<&mut T>> {
+        let poisoned = self.is_poisoned();
+        let value = self.inner.get_mut();
+        if poisoned {
+            Err(PoisonError::new(value))
+        } else {
+            Ok(value)
+        }
+    }
+
+    #[inline]
+    fn wrap<'a>(
+        &'a self,
+        guard: OneShotMutexGuard<'a, T>,
+    ) -> Result<PoisonOneShotMutexGuard<'a, T>, PoisonError<PoisonOneShotMutexGuard<'a, T>>> {
+        let guard = PoisonOneShotMutexGuard {
+            guard,
+            poisoned: &self.poisoned,
+            panicking: panicking(),
+        };
+
+        if self.is_poisoned() {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for PoisonOneShotMutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoisonOneShotMutex")
+            .field("poisoned", &self.is_poisoned())
+            .field("inner", &&self.inner)
+            .finish()
+    }
+}
+
+/// A guard of a [`PoisonOneShotMutex`].
+///
+/// If this guard is dropped during a panic, the mutex is poisoned.
+#[must_use = "if unused the PoisonOneShotMutex will immediately unlock"]
+pub struct PoisonOneShotMutexGuard<'a, T: ?Sized> {
+    guard: OneShotMutexGuard<'a, T>,
+    poisoned: &'a AtomicBool,
+    panicking: bool,
+}
+
+impl<T: ?Sized> Deref for PoisonOneShotMutexGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized> DerefMut for PoisonOneShotMutexGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<T: ?Sized> Drop for PoisonOneShotMutexGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // Only poison if the panic started while this guard was held.
+        if !self.panicking && panicking() {
+            self.poisoned.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for PoisonOneShotMutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.guard.fmt(f)
+    }
+}
+
+/// The error returned if a [`PoisonOneShotMutex`] is poisoned.
+///
+/// This carries the value that would have been returned otherwise, such as the acquired guard.
+pub struct PoisonError<T> {
+    value: T,
+}
+
+impl<T> PoisonError<T> {
+    #[inline]
+    fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// Consumes this error, returning the underlying value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Returns a reference to the underlying value.
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the underlying value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> fmt::Debug for PoisonError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoisonError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for PoisonError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("poisoned lock: another task failed inside")
+    }
+}
+
+impl<T> core::error::Error for PoisonError<T> {}
+
+#[cfg(feature = "std")]
+#[inline]
+fn panicking() -> bool {
+    std::thread::panicking()
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+fn panicking() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock() {
+        let mutex = PoisonOneShotMutex::new(42);
+        let mut guard = mutex.lock().unwrap();
+        assert_eq!(*guard, 42);
+        assert!(mutex.try_lock().is_none());
+
+        *guard += 1;
+        drop(guard);
+        assert_eq!(*mutex.try_lock().unwrap().unwrap(), 43);
+        assert!(!mutex.is_poisoned());
+        assert_eq!(mutex.into_inner().unwrap(), 43);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", panic = "unwind"))]
+    fn poison() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mut mutex = PoisonOneShotMutex::new(42);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut guard = mutex.lock().unwrap();
+            *guard += 1;
+            panic!("panicking while holding the guard");
+        }));
+        assert!(result.is_err());
+        assert!(mutex.is_poisoned());
+
+        let guard = mutex.lock().unwrap_err().into_inner();
+        assert_eq!(*guard, 43);
+        drop(guard);
+        assert!(mutex.get_mut().is_err());
+
+        mutex.clear_poison();
+        assert!(!mutex.is_poisoned());
+        assert_eq!(*mutex.lock().unwrap(), 43);
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", panic = "unwind"))]
+    fn contention_does_not_poison() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mutex = PoisonOneShotMutex::new(42);
+        let guard = mutex.lock().unwrap();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(mutex.lock())));
+        assert!(result.is_err());
+        drop(guard);
+        assert!(!mutex.is_poisoned());
+    }
+}
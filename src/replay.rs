@@ -0,0 +1,124 @@
+use core::fmt;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::global::Global;
+use crate::LockEvent;
+
+/// A lock event recorded in replay mode.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ReplayRecord {
+    /// The global sequence number of this event.
+    ///
+    /// Sequence numbers start at zero once the sink is set and increase monotonically.
+    pub seq: usize,
+    /// The address of the lock.
+    pub lock: usize,
+    /// The lock operation.
+    pub event: LockEvent,
+}
+
+/// A sink for lock events recorded in replay mode.
+///
+/// Once set via [`set_replay_sink`], every successful lock and unlock operation is recorded with a global sequence number.
+/// This allows reconstructing the exact order of events that led to contention.
+///
+/// If locks are used from multiple threads, records may arrive out of order.
+/// Sort them by [`seq`] to reconstruct the order.
+///
+/// [`seq`]: ReplayRecord::seq
+pub trait ReplaySink: Sync {
+    /// Records a lock event.
+    fn record(&self, record: ReplayRecord);
+}
+
+static SINK: Global<dyn ReplaySink> = Global::new();
+static SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the global replay sink.
+///
+/// The sink can only be set once.
+///
+/// # Errors
+///
+/// This function fails if a sink has already been set.
+pub fn set_replay_sink(sink: &'static dyn ReplaySink) -> Result<(), SetReplaySinkError> {
+    if SINK.set(sink) {
+        Ok(())
+    } else {
+        Err(SetReplaySinkError(()))
+    }
+}
+
+/// The error returned by [`set_replay_sink`] if a sink has already been set.
+#[derive(Debug)]
+pub struct SetReplaySinkError(());
+
+impl fmt::Display for SetReplaySinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a replay sink has already been set")
+    }
+}
+
+impl core::error::Error for SetReplaySinkError {}
+
+pub(crate) fn record(lock: usize, event: LockEvent) {
+    if let Some(sink) = SINK.get() {
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        sink.record(ReplayRecord { seq, lock, event });
+    }
+}
+
+/// A [`ReplaySink`] that asserts that the live execution matches a recording.
+///
+/// Since lock addresses usually differ between runs, only the sequence of [`LockEvent`]s is compared.
+///
+/// # Panics
+///
+/// Recording an event panics if it diverges from the recording.
+/// This panic happens inside the diverging lock operation after it succeeded.
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{set_replay_sink, LockEvent, OneShotMutex, ReplayVerifier};
+///
+/// static VERIFIER: ReplayVerifier = ReplayVerifier::new(&[LockEvent::Lock, LockEvent::Unlock]);
+/// set_replay_sink(&VERIFIER).unwrap();
+///
+/// let mutex = OneShotMutex::new(42);
+/// drop(mutex.lock());
+/// assert!(VERIFIER.is_complete());
+/// ```
+pub struct ReplayVerifier {
+    expected: &'static [LockEvent],
+    verified: AtomicUsize,
+}
+
+impl ReplayVerifier {
+    /// Creates a new verifier for the recorded sequence of events.
+    pub const fn new(expected: &'static [LockEvent]) -> Self {
+        Self {
+            expected,
+            verified: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns `true` if all recorded events have been verified.
+    pub fn is_complete(&self) -> bool {
+        self.verified.load(Ordering::Relaxed) == self.expected.len()
+    }
+}
+
+impl ReplaySink for ReplayVerifier {
+    fn record(&self, record: ReplayRecord) {
+        let expected = self.expected.get(record.seq);
+        if expected != Some(&record.event) {
+            panic!(
+                "replay diverged at event {}: expected {:?}, got {:?}",
+                record.seq, expected, record.event
+            );
+        }
+
+        self.verified.fetch_add(1, Ordering::Relaxed);
+    }
+}
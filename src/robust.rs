@@ -0,0 +1,72 @@
+use core::num::NonZeroU32;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// The bit offset of the owner in the lock word of robust locks.
+///
+/// Robust locks store the owner in the upper half of a 64-bit lock word,
+/// so that the owner is set by the same atomic operation that acquires the lock.
+const OWNER_SHIFT: u32 = 32;
+
+/// The bits of the lock word that hold the owner.
+pub(crate) const OWNER_MASK: u64 = !0 << OWNER_SHIFT;
+
+/// The identifier of the process owning a robust lock.
+///
+/// Robust locks record the [process ID] of their owner.
+/// This allows other processes sharing the lock via shared memory to recover it if the owner died while holding it,
+/// similar to POSIX robust mutexes.
+///
+/// [process ID]: std::process::id
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct OwnerId(NonZeroU32);
+
+impl OwnerId {
+    /// Returns the identifier of the current process.
+    ///
+    /// The process ID is cached after the first call.
+    /// A child process created with `fork` inherits the cache of its parent
+    /// and must not use robust locks unless it replaces its process image with `exec`.
+    #[inline]
+    pub fn current() -> Self {
+        static CURRENT: AtomicU32 = AtomicU32::new(0);
+
+        if let Some(id) = Self::new(CURRENT.load(Ordering::Relaxed)) {
+            return id;
+        }
+
+        let id = Self::new(std::process::id()).expect("process ID must not be zero");
+        CURRENT.store(id.get(), Ordering::Relaxed);
+        id
+    }
+
+    /// Creates an owner identifier from a process ID.
+    ///
+    /// Returns `None` if `id` is zero.
+    #[inline]
+    pub const fn new(id: u32) -> Option<Self> {
+        match NonZeroU32::new(id) {
+            Some(id) => Some(Self(id)),
+            None => None,
+        }
+    }
+
+    /// Returns the process ID.
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0.get()
+    }
+
+    /// Returns the owner bits of a lock word owned by this process.
+    #[inline]
+    pub(crate) fn to_word(self) -> u64 {
+        u64::from(self.get()) << OWNER_SHIFT
+    }
+
+    /// Extracts the owner from a lock word.
+    ///
+    /// Returns `None` if the lock word has no owner.
+    #[inline]
+    pub(crate) fn from_word(word: u64) -> Option<Self> {
+        Self::new((word >> OWNER_SHIFT) as u32)
+    }
+}
@@ -1,20 +1,33 @@
-use core::sync::atomic::{AtomicUsize, Ordering};
+use core::any::type_name;
+use core::sync::atomic::Ordering;
+use core::{fmt, mem};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
 use lock_api::{
-    GuardSend, RawRwLock, RawRwLockDowngrade, RawRwLockRecursive, RawRwLockUpgrade,
-    RawRwLockUpgradeDowngrade,
+    GuardSend, RawRwLock, RawRwLockDowngrade, RawRwLockFair, RawRwLockRecursive, RawRwLockUpgrade,
+    RawRwLockUpgradeDowngrade, RawRwLockUpgradeFair,
 };
+#[cfg(feature = "std")]
+use lock_api::{RawRwLockTimed, RawRwLockUpgradeTimed};
 
-/// A one-shot readers-writer lock that panics instead of (dead)locking on contention.
-///
-/// This lock allows no contention and panics on [`lock_shared`], [`lock_exclusive`], [`lock_upgradable`], and [`upgrade`] if it is already locked conflictingly.
-/// This is useful in situations where contention would be a bug,
-/// such as in single-threaded programs that would deadlock on contention.
-///
-/// [`lock_shared`]: RawOneShotRwLock::lock_shared
-/// [`lock_exclusive`]: RawOneShotRwLock::lock_exclusive
-/// [`lock_upgradable`]: RawOneShotRwLock::lock_upgradable
-/// [`upgrade`]: RawOneShotRwLock::upgrade
+#[cfg(feature = "robust")]
+use crate::atomic::AtomicU64 as AtomicState;
+#[cfg(feature = "metrics")]
+use crate::atomic::AtomicUsize;
+#[cfg(not(feature = "robust"))]
+use crate::atomic::AtomicUsize as AtomicState;
+use crate::contention::{contended, Contended};
+use crate::event::{emit, emit_failed, LockEvent};
+#[cfg(feature = "robust")]
+use crate::robust::OWNER_MASK;
+use crate::sealed::Sealed;
+#[cfg(feature = "robust")]
+use crate::OwnerId;
+#[cfg(feature = "std")]
+use crate::StampedGuard;
+
+/// A [`RawOneShotRwLockCapped`] without a limit on the number of shared locks.
 ///
 /// # Examples
 ///
@@ -33,308 +46,1829 @@ use lock_api::{
 /// drop(x);
 /// let x = X.write();
 /// ```
-pub struct RawOneShotRwLock {
-    lock: AtomicUsize,
+pub type RawOneShotRwLock = RawOneShotRwLockCapped<{ usize::MAX }>;
+
+/// A one-shot readers-writer lock that panics instead of (dead)locking on contention.
+///
+/// This lock allows no contention and panics on [`lock_shared`], [`lock_exclusive`], [`lock_upgradable`], and [`upgrade`] if it is already locked conflictingly.
+/// This is useful in situations where contention would be a bug,
+/// such as in single-threaded programs that would deadlock on contention.
+///
+/// Usually, this lock is used as [`RawOneShotRwLock`], which does not limit the number of shared locks.
+///
+/// [`lock_shared`]: Self::lock_shared
+/// [`lock_exclusive`]: Self::lock_exclusive
+/// [`lock_upgradable`]: Self::lock_upgradable
+/// [`upgrade`]: Self::upgrade
+///
+/// # Progress guarantees
+///
+/// [`try_lock_shared`], [`try_lock_upgradable`], [`try_lock_exclusive_weak`], and [`try_upgrade_weak`] are wait-free:
+/// each performs a bounded number of atomic operations and never loops or spins.
+/// A failed attempt to acquire the lock rolls back its changes to the lock state, if any, with a single atomic operation.
+/// This makes them suitable for real-time code.
+///
+/// The other operations are wait-free on architectures with a compare-and-swap instruction, such as x86-64.
+/// On LL/SC architectures such as AArch64 without LSE, the compare-and-swap of [`try_lock_exclusive`], [`try_upgrade`],
+/// and the corresponding panicking methods is a loop that retries if another core interferes between the load and the store.
+/// With the `robust` feature, acquiring the upgradable lock retries its compare-and-swap while shared locks are acquired or released concurrently,
+/// so it is only lock-free.
+///
+/// These guarantees only hold without instrumentation.
+/// The `tracing` and `replay` features call into other code on every operation,
+/// once per released shared lock in [`force_unlock_shared`],
+/// and a [`ContentionReporter`] runs on contention.
+///
+/// [`try_lock_upgradable`]: Self::try_lock_upgradable
+/// [`try_lock_exclusive_weak`]: Self::try_lock_exclusive_weak
+/// [`try_upgrade_weak`]: Self::try_upgrade_weak
+/// [`try_lock_exclusive`]: Self::try_lock_exclusive
+/// [`try_upgrade`]: Self::try_upgrade
+/// [`force_unlock_shared`]: Self::force_unlock_shared
+/// [`ContentionReporter`]: crate::ContentionReporter
+///
+/// # Capping readers
+///
+/// With `MAX` below `usize::MAX`, this lock allows at most `MAX` shared locks at the same time.
+/// This models a resource with bounded fan-out.
+/// [`try_lock_shared`] fails and [`lock_shared`] panics if `MAX` shared locks are already held.
+/// The upgradable lock does not count towards `MAX`, and downgrading does not check it.
+///
+/// [`try_lock_shared`]: Self::try_lock_shared
+///
+/// ```
+/// use one_shot_mutex::OneShotRwLockCapped;
+///
+/// let lock = OneShotRwLockCapped::<_, 2>::new(42);
+///
+/// let x = lock.read();
+/// let y = lock.read();
+/// assert!(lock.try_read().is_none());
+/// ```
+pub struct RawOneShotRwLockCapped<const MAX: usize> {
+    lock: AtomicState,
+    #[cfg(feature = "metrics")]
+    counters: Counters,
 }
 
+/// The lock state.
+///
+/// With the `robust` feature, the upper half holds the process owning the upgradable lock,
+/// so that both are set by the same atomic operation.
+#[cfg(not(feature = "robust"))]
+type State = usize;
+#[cfg(feature = "robust")]
+type State = u64;
+
 /// Normal shared lock counter
-const SHARED: usize = 1 << 2;
+const SHARED: State = 1 << 2;
 /// Special upgradable shared lock flag
-const UPGRADABLE: usize = 1 << 1;
+const UPGRADABLE: State = 1 << 1;
 /// Exclusive lock flag
-const EXCLUSIVE: usize = 1;
+const EXCLUSIVE: State = 1;
+/// The owner of the upgradable lock
+#[cfg(not(feature = "robust"))]
+const OWNER_MASK: State = 0;
+/// The shared lock counter bits
+const SHARED_MASK: State = !(EXCLUSIVE | UPGRADABLE | OWNER_MASK);
 
-impl RawOneShotRwLock {
-    #[inline]
-    fn is_locked_shared(&self) -> bool {
-        self.lock.load(Ordering::Relaxed) & !(EXCLUSIVE | UPGRADABLE) != 0
-    }
+/// Returns the number of shared locks in `value`.
+#[inline]
+#[allow(clippy::unnecessary_cast)] // `State` is `usize` without the `robust` feature.
+fn readers(value: State) -> usize {
+    ((value & SHARED_MASK) / SHARED) as usize
+}
 
-    #[inline]
-    fn is_locked_upgradable(&self) -> bool {
-        self.lock.load(Ordering::Relaxed) & UPGRADABLE == UPGRADABLE
+/// Returns the state of an upgradable lock held by the current context without any readers.
+#[inline]
+fn upgradable() -> State {
+    #[cfg(feature = "robust")]
+    return UPGRADABLE | OwnerId::current().to_word();
+    #[cfg(not(feature = "robust"))]
+    UPGRADABLE
+}
+
+/// Compares and swaps the lock value with [`Acquire`] ordering on success.
+///
+/// If `weak` is true, this may fail spuriously.
+/// On LL/SC architectures, this avoids the retry loop of the strong version, see `RawOneShotMutex::acquire`.
+///
+/// [`Acquire`]: Ordering::Acquire
+#[inline]
+fn compare_exchange(
+    lock: &AtomicState,
+    current: State,
+    new: State,
+    weak: bool,
+) -> Result<State, State> {
+    if weak {
+        lock.compare_exchange_weak(current, new, Ordering::Acquire, Ordering::Relaxed)
+    } else {
+        lock.compare_exchange(current, new, Ordering::Acquire, Ordering::Relaxed)
     }
+}
 
-    /// Acquire a shared lock, returning the new lock value.
-    #[inline]
-    fn acquire_shared(&self) -> usize {
-        let value = self.lock.fetch_add(SHARED, Ordering::Acquire);
+/// The acquisitions that would currently succeed on a [`RawOneShotRwLock`].
+///
+/// This is returned by [`RawOneShotRwLockCapped::admission`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Admission {
+    /// Whether a shared lock can be acquired.
+    pub can_share: bool,
+    /// Whether an upgradable lock can be acquired.
+    pub can_upgradable: bool,
+    /// Whether an exclusive lock can be acquired.
+    pub can_exclusive: bool,
+}
 
-        // An arbitrary cap that allows us to catch overflows long before they happen
-        if value > usize::MAX / 2 {
-            self.lock.fetch_sub(SHARED, Ordering::Relaxed);
-            panic!("Too many shared locks, cannot safely proceed");
+/// Statistics about the successful acquisitions of a [`RawOneShotRwLock`].
+///
+/// This is returned by [`RawOneShotRwLockCapped::access_stats`].
+#[cfg(feature = "metrics")]
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct AccessStats {
+    /// The number of shared acquisitions.
+    pub reads: usize,
+    /// The number of exclusive acquisitions.
+    pub writes: usize,
+    /// The number of upgradable acquisitions.
+    pub upgradable_reads: usize,
+    /// The number of upgrades from upgradable to exclusive.
+    pub upgrades: usize,
+}
+
+#[cfg(feature = "metrics")]
+struct Counters {
+    reads: AtomicUsize,
+    writes: AtomicUsize,
+    upgradable_reads: AtomicUsize,
+    upgrades: AtomicUsize,
+}
+
+#[cfg(feature = "metrics")]
+impl Counters {
+    const fn new() -> Self {
+        Self {
+            reads: AtomicUsize::new(0),
+            writes: AtomicUsize::new(0),
+            upgradable_reads: AtomicUsize::new(0),
+            upgrades: AtomicUsize::new(0),
         }
+    }
 
-        value
+    #[inline]
+    fn increment(counter: &AtomicUsize) {
+        counter.fetch_add(1, Ordering::Relaxed);
     }
 }
 
-unsafe impl RawRwLock for RawOneShotRwLock {
-    #[allow(clippy::declare_interior_mutable_const)]
-    const INIT: Self = Self {
-        lock: AtomicUsize::new(0),
-    };
-
-    type GuardMarker = GuardSend;
+impl<const MAX: usize> RawOneShotRwLockCapped<MAX> {
+    /// Creates a new unlocked lock.
+    ///
+    /// This is equivalent to [`RawRwLock::INIT`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::RawOneShotRwLock;
+    ///
+    /// static X: RawOneShotRwLock = RawOneShotRwLock::new();
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self::INIT
+    }
 
+    /// Acquires a shared lock.
+    ///
+    /// This is equivalent to [`RawRwLock::lock_shared`] but does not require the trait to be in scope.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lock is already locked exclusively or by the maximum number of readers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::RawOneShotRwLock;
+    ///
+    /// let lock = RawOneShotRwLock::new();
+    /// lock.lock_shared();
+    /// assert!(lock.try_lock_shared());
+    /// assert!(!lock.try_lock_exclusive());
+    /// unsafe {
+    ///     lock.unlock_shared();
+    ///     lock.unlock_shared();
+    /// }
+    ///
+    /// lock.lock_exclusive();
+    /// assert!(lock.is_locked_exclusive());
+    /// unsafe { lock.unlock_exclusive() }
+    /// assert!(!lock.is_locked());
+    /// ```
     #[inline]
-    fn lock_shared(&self) {
-        assert!(
-            self.try_lock_shared(),
-            "called `lock_shared` on a `RawOneShotRwLock` that is already locked exclusively"
-        );
+    #[track_caller]
+    pub fn lock_shared(&self) {
+        <Self as RawRwLock>::lock_shared(self);
     }
 
+    /// Attempts to acquire a shared lock.
+    ///
+    /// This is equivalent to [`RawRwLock::try_lock_shared`] but does not require the trait to be in scope.
     #[inline]
-    fn try_lock_shared(&self) -> bool {
-        let value = self.acquire_shared();
+    #[cfg_attr(feature = "tracing", track_caller)]
+    pub fn try_lock_shared(&self) -> bool {
+        <Self as RawRwLock>::try_lock_shared(self)
+    }
 
-        let acquired = value & EXCLUSIVE != EXCLUSIVE;
+    /// Releases a shared lock.
+    ///
+    /// This is equivalent to [`RawRwLock::unlock_shared`] but does not require the trait to be in scope.
+    ///
+    /// # Safety
+    ///
+    /// This method may only be called if a shared lock is held in the current context, see [`RawRwLock::unlock_shared`].
+    #[inline]
+    pub unsafe fn unlock_shared(&self) {
+        unsafe { <Self as RawRwLock>::unlock_shared(self) }
+    }
 
-        if !acquired {
-            unsafe {
-                self.unlock_shared();
-            }
-        }
+    /// Acquires an exclusive lock.
+    ///
+    /// This is equivalent to [`RawRwLock::lock_exclusive`] but does not require the trait to be in scope.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lock is already locked.
+    #[inline]
+    #[track_caller]
+    pub fn lock_exclusive(&self) {
+        <Self as RawRwLock>::lock_exclusive(self);
+    }
 
-        acquired
+    /// Attempts to acquire an exclusive lock.
+    ///
+    /// This is equivalent to [`RawRwLock::try_lock_exclusive`] but does not require the trait to be in scope.
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    pub fn try_lock_exclusive(&self) -> bool {
+        <Self as RawRwLock>::try_lock_exclusive(self)
     }
 
+    /// Attempts to acquire an exclusive lock, possibly failing spuriously.
+    ///
+    /// Unlike [`try_lock_exclusive`], this may fail even if the lock is unlocked.
+    /// See [`RawOneShotMutex::try_lock_weak`] for when this is cheaper.
+    ///
+    /// [`try_lock_exclusive`]: Self::try_lock_exclusive
+    /// [`RawOneShotMutex::try_lock_weak`]: crate::RawOneShotMutex::try_lock_weak
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::RawOneShotRwLock;
+    ///
+    /// let lock = RawOneShotRwLock::new();
+    ///
+    /// // Spurious failures do not prevent eventual success.
+    /// while !lock.try_lock_exclusive_weak() {}
+    /// assert!(!lock.try_lock_exclusive_weak());
+    /// unsafe { lock.unlock_exclusive() }
+    /// ```
     #[inline]
-    unsafe fn unlock_shared(&self) {
-        debug_assert!(self.is_locked_shared());
+    #[cfg_attr(feature = "tracing", track_caller)]
+    pub fn try_lock_exclusive_weak(&self) -> bool {
+        self.try_acquire_exclusive(true)
+    }
 
-        self.lock.fetch_sub(SHARED, Ordering::Release);
+    /// Attempts to upgrade an upgradable lock to an exclusive lock, possibly failing spuriously.
+    ///
+    /// Unlike [`RawRwLockUpgrade::try_upgrade`], this may fail even if no shared locks are held.
+    /// See [`RawOneShotMutex::try_lock_weak`] for when this is cheaper.
+    ///
+    /// [`RawOneShotMutex::try_lock_weak`]: crate::RawOneShotMutex::try_lock_weak
+    ///
+    /// # Safety
+    ///
+    /// This method may only be called if an upgradable lock is held in the current context, see [`RawRwLockUpgrade::try_upgrade`].
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    pub unsafe fn try_upgrade_weak(&self) -> bool {
+        self.try_upgrade_to_exclusive(true)
     }
 
+    /// Releases an exclusive lock.
+    ///
+    /// This is equivalent to [`RawRwLock::unlock_exclusive`] but does not require the trait to be in scope.
+    ///
+    /// # Safety
+    ///
+    /// This method may only be called if an exclusive lock is held in the current context, see [`RawRwLock::unlock_exclusive`].
     #[inline]
-    fn lock_exclusive(&self) {
-        assert!(
-            self.try_lock_exclusive(),
-            "called `lock_exclusive` on a `RawOneShotRwLock` that is already locked"
-        );
+    pub unsafe fn unlock_exclusive(&self) {
+        unsafe { <Self as RawRwLock>::unlock_exclusive(self) }
     }
 
+    /// Checks whether this lock is currently locked in any way.
+    ///
+    /// This is equivalent to [`RawRwLock::is_locked`] but does not require the trait to be in scope.
     #[inline]
-    fn try_lock_exclusive(&self) -> bool {
-        self.lock
-            .compare_exchange(0, EXCLUSIVE, Ordering::Acquire, Ordering::Relaxed)
-            .is_ok()
+    pub fn is_locked(&self) -> bool {
+        <Self as RawRwLock>::is_locked(self)
     }
 
+    /// Checks whether this lock is currently locked exclusively.
+    ///
+    /// This is equivalent to [`RawRwLock::is_locked_exclusive`] but does not require the trait to be in scope.
     #[inline]
-    unsafe fn unlock_exclusive(&self) {
-        debug_assert!(self.is_locked_exclusive());
+    pub fn is_locked_exclusive(&self) -> bool {
+        <Self as RawRwLock>::is_locked_exclusive(self)
+    }
 
-        self.lock.fetch_and(!EXCLUSIVE, Ordering::Release);
+    /// Returns statistics about the successful acquisitions of this lock.
+    ///
+    /// This helps judging the read/write ratio of a lock,
+    /// for example, to decide whether a mutex would suffice.
+    /// Each counter is read individually, so the statistics may be inconsistent if the lock is used concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotRwLock, OneShotRwLockExt};
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// drop(lock.read());
+    /// drop(lock.write());
+    ///
+    /// let stats = lock.access_stats();
+    /// assert_eq!(stats.reads, 1);
+    /// assert_eq!(stats.writes, 1);
+    /// ```
+    #[cfg(feature = "metrics")]
+    pub fn access_stats(&self) -> AccessStats {
+        AccessStats {
+            reads: self.counters.reads.load(Ordering::Relaxed),
+            writes: self.counters.writes.load(Ordering::Relaxed),
+            upgradable_reads: self.counters.upgradable_reads.load(Ordering::Relaxed),
+            upgrades: self.counters.upgrades.load(Ordering::Relaxed),
+        }
     }
 
+    /// Returns the process that holds the upgradable lock.
+    ///
+    /// Returns `None` if the lock is not locked upgradably.
+    /// This may be stale if the lock is used concurrently.
+    #[cfg(feature = "robust")]
     #[inline]
-    fn is_locked(&self) -> bool {
-        self.lock.load(Ordering::Relaxed) != 0
+    pub fn upgradable_owner(&self) -> Option<OwnerId> {
+        OwnerId::from_word(self.lock.load(Ordering::Relaxed))
+    }
+
+    /// Attempts to acquire an exclusive lock, reclaiming it from a dead upgradable owner.
+    ///
+    /// If the lock cannot be acquired exclusively because it is only held by a lone upgradable lock,
+    /// this calls `is_alive` with the process that holds the upgradable lock.
+    /// If that returns `false`, the upgradable lock is taken over and turned into an exclusive lock.
+    /// Shared locks are not tracked and cannot be reclaimed.
+    ///
+    /// Returns `true` if the exclusive lock was acquired.
+    ///
+    /// Note that the protected data may be in an inconsistent state after reclaiming,
+    /// since the owner may have died while modifying it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotRwLock;
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// let guard = lock.upgradable_read();
+    ///
+    /// // Pretend that this process died.
+    /// core::mem::forget(guard);
+    /// let raw = unsafe { lock.raw() };
+    /// assert!(raw.try_lock_exclusive_reclaiming(|_owner| false));
+    /// ```
+    #[cfg(feature = "robust")]
+    pub fn try_lock_exclusive_reclaiming(&self, is_alive: impl Fn(OwnerId) -> bool) -> bool {
+        if self.try_lock_exclusive_strong() {
+            return true;
+        }
+
+        let value = self.lock.load(Ordering::Relaxed);
+        if value & !OWNER_MASK != UPGRADABLE {
+            return false;
+        }
+
+        let Some(owner) = OwnerId::from_word(value) else {
+            return false;
+        };
+
+        if is_alive(owner) {
+            return false;
+        }
+
+        // Only one reclaiming process may succeed,
+        // and only if no shared lock has been acquired and the upgradable lock has not changed hands in the meantime.
+        if self
+            .lock
+            .compare_exchange(value, EXCLUSIVE, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+
+        self.acquired_exclusive();
+
+        true
     }
 
+    /// Returns which acquisitions would currently succeed.
+    ///
+    /// The result is decoded from a single load of the lock state, without modifying it.
+    /// It may be outdated as soon as it is returned if the lock is used concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotRwLock;
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// let guard = lock.upgradable_read();
+    ///
+    /// let admission = unsafe { lock.raw() }.admission();
+    /// assert!(admission.can_share);
+    /// assert!(!admission.can_upgradable);
+    /// assert!(!admission.can_exclusive);
+    /// ```
     #[inline]
-    fn is_locked_exclusive(&self) -> bool {
-        self.lock.load(Ordering::Relaxed) & EXCLUSIVE == EXCLUSIVE
+    pub fn admission(&self) -> Admission {
+        let value = self.lock.load(Ordering::Relaxed);
+
+        Admission {
+            can_share: value & EXCLUSIVE == 0 && readers(value) < MAX,
+            can_upgradable: value & (UPGRADABLE | EXCLUSIVE) == 0,
+            can_exclusive: value == 0,
+        }
     }
-}
 
-unsafe impl RawRwLockRecursive for RawOneShotRwLock {
+    /// Checks whether this lock is currently locked in any way with [`Acquire`] ordering.
+    ///
+    /// [`is_locked`] uses [`Relaxed`] ordering, which is sufficient for diagnostics and assertions.
+    /// Use this method instead if the observation is used for synchronization:
+    /// if this returns `false`, all accesses to the protected data by previous holders of the lock happen before this call.
+    ///
+    /// [`Acquire`]: Ordering::Acquire
+    /// [`is_locked`]: Self::is_locked
+    /// [`Relaxed`]: Ordering::Relaxed
     #[inline]
-    fn lock_shared_recursive(&self) {
-        self.lock_shared();
+    pub fn is_locked_acquire(&self) -> bool {
+        self.lock.load(Ordering::Acquire) != 0
     }
 
+    /// Checks whether this lock is currently locked exclusively with [`Acquire`] ordering.
+    ///
+    /// Like [`is_locked_acquire`], this is meant for synchronization, while [`is_locked_exclusive`] is meant for diagnostics.
+    /// If this returns `false`, all writes to the protected data by previous exclusive holders of the lock happen before this call.
+    ///
+    /// [`Acquire`]: Ordering::Acquire
+    /// [`is_locked_acquire`]: Self::is_locked_acquire
+    /// [`is_locked_exclusive`]: Self::is_locked_exclusive
     #[inline]
-    fn try_lock_shared_recursive(&self) -> bool {
-        self.try_lock_shared()
+    pub fn is_locked_exclusive_acquire(&self) -> bool {
+        self.lock.load(Ordering::Acquire) & EXCLUSIVE == EXCLUSIVE
     }
-}
 
-unsafe impl RawRwLockDowngrade for RawOneShotRwLock {
+    /// Forcibly releases the exclusive lock.
+    ///
+    /// This clears the exclusive lock, regardless of whether and by whom it is held.
+    /// This is useful for recovering a lock after its write guard has been leaked, for example, with [`mem::forget`].
+    ///
+    /// [`mem::forget`]: core::mem::forget
+    ///
+    /// # Safety
+    ///
+    /// No write guard of this lock may be alive, and nobody may access the protected data through the exclusive lock.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotRwLock;
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// core::mem::forget(lock.write());
+    ///
+    /// unsafe { lock.raw().force_unlock_exclusive() }
+    /// let guard = lock.write();
+    /// ```
     #[inline]
-    unsafe fn downgrade(&self) {
-        // Reserve the shared guard for ourselves
-        self.acquire_shared();
+    pub unsafe fn force_unlock_exclusive(&self) {
+        let value = self.lock.fetch_and(!EXCLUSIVE, Ordering::Release);
 
-        unsafe {
-            self.unlock_exclusive();
+        if value & EXCLUSIVE == EXCLUSIVE {
+            emit(self, LockEvent::UnlockExclusive);
         }
     }
-}
 
-unsafe impl RawRwLockUpgrade for RawOneShotRwLock {
+    /// Forcibly releases all shared locks.
+    ///
+    /// This clears all shared locks, regardless of how many are held and by whom.
+    /// The upgradable lock is not released.
+    /// This is useful for recovering a lock after its read guards have been leaked, for example, with [`mem::forget`].
+    ///
+    /// [`mem::forget`]: core::mem::forget
+    /// [`leak_read`]: crate::leak_read
+    ///
+    /// # Safety
+    ///
+    /// No read guard of this lock may be alive, and nobody may access the protected data through a shared lock (including references returned by [`leak_read`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotRwLock;
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// core::mem::forget(lock.read());
+    /// core::mem::forget(lock.read());
+    ///
+    /// unsafe { lock.raw().force_unlock_shared() }
+    /// let guard = lock.write();
+    /// ```
     #[inline]
-    fn lock_upgradable(&self) {
-        assert!(
-            self.try_lock_upgradable(),
-            "called `lock_upgradable` on a `RawOneShotRwLock` that is already locked upgradably or exclusively"
-        );
+    pub unsafe fn force_unlock_shared(&self) {
+        let value = self.lock.fetch_and(!SHARED_MASK, Ordering::Release);
+
+        for _ in 0..readers(value) {
+            emit(self, LockEvent::UnlockShared);
+        }
     }
 
+    /// Acquires an exclusive lock without checking for contention in release builds.
+    ///
+    /// With debug assertions, this behaves like [`lock_exclusive`] and panics on contention.
+    /// Without debug assertions, this marks the lock as exclusively locked with a plain store instead of a compare-and-swap.
+    /// This is meant for hot paths where contention has been ruled out by other means,
+    /// such as in single-threaded programs.
+    ///
+    /// [`lock_exclusive`]: Self::lock_exclusive
+    ///
+    /// # Safety
+    ///
+    /// The lock must be unlocked, and the previous unlock must happen before this call.
+    /// Nobody may try to lock the lock concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotRwLock;
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    ///
+    /// // SAFETY: Nobody else has access to the lock.
+    /// let guard = unsafe {
+    ///     lock.raw().lock_exclusive_unchecked();
+    ///     lock.make_write_guard_unchecked()
+    /// };
+    /// assert_eq!(*guard, 42);
+    /// ```
     #[inline]
-    fn try_lock_upgradable(&self) -> bool {
-        let value = self.lock.fetch_or(UPGRADABLE, Ordering::Acquire);
-
-        let acquired = value & (UPGRADABLE | EXCLUSIVE) == 0;
-
-        if !acquired && value & UPGRADABLE == 0 {
-            unsafe {
-                self.unlock_upgradable();
+    #[track_caller]
+    pub unsafe fn lock_exclusive_unchecked(&self) {
+        if cfg!(debug_assertions) {
+            if !self.try_lock_exclusive_strong() {
+                contended("lock_exclusive_unchecked", Self::name(), "already locked");
             }
+        } else {
+            self.lock.store(EXCLUSIVE, Ordering::Relaxed);
+            self.acquired_exclusive();
         }
-
-        acquired
     }
 
     #[inline]
-    unsafe fn unlock_upgradable(&self) {
-        debug_assert!(self.is_locked_upgradable());
-
-        self.lock.fetch_and(!UPGRADABLE, Ordering::Release);
+    fn is_locked_shared(&self) -> bool {
+        self.lock.load(Ordering::Relaxed) & SHARED_MASK != 0
     }
 
+    /// Checks whether this lock is currently locked upgradably.
+    ///
+    /// This uses [`Relaxed`] ordering and is meant for diagnostics and assertions.
+    ///
+    /// [`Relaxed`]: Ordering::Relaxed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotRwLock;
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// let guard = lock.upgradable_read();
+    /// assert!(unsafe { lock.raw() }.is_locked_upgradable());
+    /// ```
     #[inline]
-    unsafe fn upgrade(&self) {
-        assert!(
-            self.try_upgrade(),
-            "called `upgrade` on a `RawOneShotRwLock` that is also locked shared by others"
-        );
+    pub fn is_locked_upgradable(&self) -> bool {
+        self.lock.load(Ordering::Relaxed) & UPGRADABLE == UPGRADABLE
     }
 
+    /// Returns the number of shared locks that are currently held.
+    ///
+    /// This does not include the upgradable lock.
+    /// This uses [`Relaxed`] ordering and is meant for diagnostics and assertions.
+    ///
+    /// [`Relaxed`]: Ordering::Relaxed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::OneShotRwLock;
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// let guard = lock.read();
+    /// let guard2 = lock.read();
+    /// let guard3 = lock.upgradable_read();
+    /// assert_eq!(unsafe { lock.raw() }.reader_count(), 2);
+    /// ```
     #[inline]
-    unsafe fn try_upgrade(&self) -> bool {
-        self.lock
-            .compare_exchange(UPGRADABLE, EXCLUSIVE, Ordering::Acquire, Ordering::Relaxed)
-            .is_ok()
+    pub fn reader_count(&self) -> usize {
+        readers(self.lock.load(Ordering::Relaxed))
     }
-}
 
-unsafe impl RawRwLockUpgradeDowngrade for RawOneShotRwLock {
+    /// Acquire a shared lock, returning the new lock value.
     #[inline]
-    unsafe fn downgrade_upgradable(&self) {
-        self.acquire_shared();
+    fn acquire_shared(&self) -> State {
+        let value = self.lock.fetch_add(SHARED, Ordering::Acquire);
 
-        unsafe {
-            self.unlock_upgradable();
+        // An arbitrary cap that allows us to catch overflows long before they happen
+        if value & SHARED_MASK > SHARED_MASK / 2 {
+            self.lock.fetch_sub(SHARED, Ordering::Relaxed);
+            panic!("Too many shared locks, cannot safely proceed");
         }
+
+        value
     }
 
+    /// Attempts to acquire an upgradable lock, returning the conflicting lock value on failure.
     #[inline]
-    unsafe fn downgrade_to_upgradable(&self) {
-        debug_assert!(self.is_locked_exclusive());
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn acquire_upgradable(&self) -> Result<(), State> {
+        if let Err(value) = self.set_upgradable() {
+            emit_failed(self, "try_lock_upgradable");
+            return Err(value);
+        }
 
-        self.lock
-            .fetch_xor(UPGRADABLE | EXCLUSIVE, Ordering::Release);
+        #[cfg(feature = "metrics")]
+        Counters::increment(&self.counters.upgradable_reads);
+        emit(self, LockEvent::LockUpgradable);
+
+        Ok(())
     }
-}
 
-/// A [`lock_api::RwLock`] based on [`RawOneShotRwLock`].
-pub type OneShotRwLock<T> = lock_api::RwLock<RawOneShotRwLock, T>;
+    /// Returns the name of this lock for diagnostics, such as `RawOneShotRwLockCapped<2>`.
+    fn name() -> &'static str {
+        if MAX == usize::MAX {
+            return "RawOneShotRwLock";
+        }
 
-/// A [`lock_api::RwLockReadGuard`] based on [`RawOneShotRwLock`].
-pub type OneShotRwLockReadGuard<'a, T> = lock_api::RwLockReadGuard<'a, RawOneShotRwLock, T>;
+        // Strip the module path.
+        let name = type_name::<Self>();
+        name.rfind("RawOneShotRwLockCapped")
+            .map_or(name, |start| &name[start..])
+    }
 
-/// A [`lock_api::RwLockUpgradableReadGuard`] based on [`RawOneShotRwLock`].
-pub type OneShotRwLockUpgradableReadGuard<'a, T> =
-    lock_api::RwLockUpgradableReadGuard<'a, RawOneShotRwLock, T>;
+    /// Sets the upgradable flag unless the lock is locked upgradably or exclusively, returning the conflicting lock value on failure.
+    #[cfg(not(feature = "robust"))]
+    #[inline]
+    fn set_upgradable(&self) -> Result<(), State> {
+        let value = self.lock.fetch_or(UPGRADABLE, Ordering::Acquire);
 
-/// A [`lock_api::RwLockWriteGuard`] based on [`RawOneShotRwLock`].
-pub type OneShotRwLockWriteGuard<'a, T> = lock_api::RwLockWriteGuard<'a, RawOneShotRwLock, T>;
+        if value & (UPGRADABLE | EXCLUSIVE) != 0 {
+            if value & UPGRADABLE == 0 {
+                self.release_upgradable();
+            }
+            return Err(value);
+        }
 
-#[cfg(test)]
-mod tests {
-    use lock_api::RwLockUpgradableReadGuard;
+        Ok(())
+    }
 
-    use super::*;
+    /// Sets the upgradable flag and its owner unless the lock is locked upgradably or exclusively, returning the conflicting lock value on failure.
+    #[cfg(feature = "robust")]
+    #[inline]
+    fn set_upgradable(&self) -> Result<(), State> {
+        let mut value = self.lock.load(Ordering::Relaxed);
 
-    #[test]
-    fn lock_exclusive() {
-        let lock = OneShotRwLock::new(42);
-        let mut guard = lock.write();
-        assert_eq!(*guard, 42);
+        // Unlike `fetch_or`, a compare-and-swap can set the owner together with the flag.
+        // This has to be retried if shared locks are acquired or released concurrently.
+        loop {
+            if value & (UPGRADABLE | EXCLUSIVE) != 0 {
+                return Err(value);
+            }
 
-        *guard += 1;
-        drop(guard);
-        let guard = lock.write();
-        assert_eq!(*guard, 43);
+            match self.lock.compare_exchange_weak(
+                value,
+                value | upgradable(),
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Ok(()),
+                Err(new) => value = new,
+            }
+        }
     }
 
-    #[test]
-    #[should_panic]
-    fn lock_exclusive_panic() {
-        let lock = OneShotRwLock::new(42);
-        let _guard = lock.write();
-        let _guard2 = lock.write();
+    /// Attempts to acquire an exclusive lock without checking whether it is locked first.
+    ///
+    /// This is used by locks that panic on contention instead of returning, since they expect to succeed.
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    pub(crate) fn try_lock_exclusive_strong(&self) -> bool {
+        self.acquire_exclusive(false)
     }
 
-    #[test]
-    #[should_panic]
-    fn lock_exclusive_shared_panic() {
-        let lock = OneShotRwLock::new(42);
-        let _guard = lock.write();
-        let _guard2 = lock.read();
+    /// Attempts to acquire an upgradable lock, returning why it is contended on failure.
+    ///
+    /// This is used by locks that panic on contention with their own message.
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    pub(crate) fn try_lock_upgradable_with_reason(&self) -> Result<(), &'static str> {
+        self.acquire_upgradable().map_err(|value| {
+            if value & EXCLUSIVE == EXCLUSIVE {
+                "already locked exclusively"
+            } else if value & !OWNER_MASK == UPGRADABLE {
+                // Without any readers, the most likely culprit is the caller itself.
+                "already locked upgradably (is the upgradable guard held recursively?)"
+            } else {
+                "already locked upgradably and shared"
+            }
+        })
     }
 
-    #[test]
-    fn try_lock_exclusive() {
-        let lock = OneShotRwLock::new(42);
-        let mut guard = lock.try_write().unwrap();
-        assert_eq!(*guard, 42);
-        assert!(lock.try_write().is_none());
+    /// Attempts to acquire an exclusive lock if a plain load shows that it is unlocked.
+    ///
+    /// If `weak` is true, this may fail spuriously even if the lock is unlocked.
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_acquire_exclusive(&self, weak: bool) -> bool {
+        // Like `try_lock_shared`, avoid the read-modify-write if it is going to fail anyway.
+        if self.is_locked() {
+            emit_failed(self, "try_lock_exclusive");
+            return false;
+        }
 
-        *guard += 1;
-        drop(guard);
-        let guard = lock.try_write().unwrap();
-        assert_eq!(*guard, 43);
+        self.acquire_exclusive(weak)
     }
 
-    #[test]
-    fn lock_shared() {
-        let lock = OneShotRwLock::new(42);
-        let guard = lock.read();
-        assert_eq!(*guard, 42);
-        let guard2 = lock.read();
-        assert_eq!(*guard2, 42);
+    /// Attempts to acquire an exclusive lock.
+    ///
+    /// If `weak` is true, this may fail spuriously even if the lock is unlocked.
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn acquire_exclusive(&self, weak: bool) -> bool {
+        let acquired = compare_exchange(&self.lock, 0, EXCLUSIVE, weak).is_ok();
+
+        if acquired {
+            self.acquired_exclusive();
+        } else {
+            emit_failed(self, "try_lock_exclusive");
+        }
+
+        acquired
     }
 
-    #[test]
-    #[should_panic]
-    fn lock_shared_panic() {
-        let lock = OneShotRwLock::new(42);
-        let _guard = lock.write();
-        let _guard2 = lock.read();
+    /// Records that the caller has acquired an exclusive lock.
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn acquired_exclusive(&self) {
+        #[cfg(feature = "metrics")]
+        Counters::increment(&self.counters.writes);
+        emit(self, LockEvent::LockExclusive);
     }
 
-    #[test]
-    fn try_lock_shared() {
-        let lock = OneShotRwLock::new(42);
-        let guard = lock.try_read().unwrap();
-        assert_eq!(*guard, 42);
-        assert!(lock.try_write().is_none());
+    /// Attempts to upgrade the upgradable lock if a plain load shows that no shared locks are held.
+    ///
+    /// If `weak` is true, this may fail spuriously even if no shared locks are held.
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_upgrade_to_exclusive(&self, weak: bool) -> bool {
+        // Like `try_lock_shared`, avoid the read-modify-write if it is going to fail anyway.
+        if self.lock.load(Ordering::Relaxed) != upgradable() {
+            emit_failed(self, "try_upgrade");
+            return false;
+        }
 
-        let guard2 = lock.try_read().unwrap();
-        assert_eq!(*guard2, 42);
+        self.upgrade_to_exclusive(weak).is_ok()
     }
 
-    #[test]
-    fn lock_upgradable() {
-        let lock = OneShotRwLock::new(42);
-        let guard = lock.upgradable_read();
-        assert_eq!(*guard, 42);
-        assert!(lock.try_write().is_none());
+    /// Attempts to upgrade the upgradable lock, returning the conflicting lock value on failure.
+    ///
+    /// If `weak` is true, this may fail spuriously even if no shared locks are held.
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn upgrade_to_exclusive(&self, weak: bool) -> Result<(), State> {
+        if let Err(value) = compare_exchange(&self.lock, upgradable(), EXCLUSIVE, weak) {
+            emit_failed(self, "try_upgrade");
+            return Err(value);
+        }
 
-        let mut upgraded = RwLockUpgradableReadGuard::upgrade(guard);
-        *upgraded += 1;
-        drop(upgraded);
+        #[cfg(feature = "metrics")]
+        Counters::increment(&self.counters.upgrades);
+        emit(self, LockEvent::Upgrade);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn release_shared(&self) {
+        debug_assert!(self.is_locked_shared());
+
+        self.lock.fetch_sub(SHARED, Ordering::Release);
+    }
+
+    #[inline]
+    fn release_exclusive(&self) {
+        debug_assert!(self.is_locked_exclusive());
+
+        self.lock.fetch_and(!EXCLUSIVE, Ordering::Release);
+    }
+
+    #[inline]
+    fn release_upgradable(&self) {
+        debug_assert!(self.is_locked_upgradable());
+
+        self.lock
+            .fetch_and(!(UPGRADABLE | OWNER_MASK), Ordering::Release);
+    }
+}
+
+impl<const MAX: usize> fmt::Debug for RawOneShotRwLockCapped<MAX> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(Self::name())
+            .field("exclusive", &self.is_locked_exclusive())
+            .field("upgradable", &self.is_locked_upgradable())
+            .field("shared", &self.reader_count())
+            .finish()
+    }
+}
+
+unsafe impl<const MAX: usize> RawRwLock for RawOneShotRwLockCapped<MAX> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        lock: AtomicState::new(0),
+        #[cfg(feature = "metrics")]
+        counters: Counters::new(),
+    };
+
+    type GuardMarker = GuardSend;
+
+    #[inline]
+    #[track_caller]
+    fn lock_shared(&self) {
+        if !self.try_lock_shared() {
+            let reason = if self.is_locked_exclusive() {
+                "already locked exclusively"
+            } else {
+                "already locked shared by the maximum number of readers"
+            };
+            contended("lock_shared", Self::name(), reason);
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_shared(&self) -> bool {
+        // Avoid the read-modify-write round trip if we are going to fail anyway.
+        // If the lock is acquired exclusively after this check, we roll back below.
+        if self.is_locked_exclusive() {
+            emit_failed(self, "try_lock_shared");
+            return false;
+        }
+
+        let value = self.acquire_shared();
+
+        let acquired = value & EXCLUSIVE != EXCLUSIVE && readers(value) < MAX;
+
+        if acquired {
+            #[cfg(feature = "metrics")]
+            Counters::increment(&self.counters.reads);
+            emit(self, LockEvent::LockShared);
+        } else {
+            self.release_shared();
+            emit_failed(self, "try_lock_shared");
+        }
+
+        acquired
+    }
+
+    #[inline]
+    unsafe fn unlock_shared(&self) {
+        emit(self, LockEvent::UnlockShared);
+        self.release_shared();
+    }
+
+    #[inline]
+    #[track_caller]
+    fn lock_exclusive(&self) {
+        if !self.try_lock_exclusive_strong() {
+            contended("lock_exclusive", Self::name(), "already locked");
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_exclusive(&self) -> bool {
+        self.try_acquire_exclusive(false)
+    }
+
+    #[inline]
+    unsafe fn unlock_exclusive(&self) {
+        emit(self, LockEvent::UnlockExclusive);
+        self.release_exclusive();
+    }
+
+    #[inline]
+    fn is_locked(&self) -> bool {
+        self.lock.load(Ordering::Relaxed) != 0
+    }
+
+    #[inline]
+    fn is_locked_exclusive(&self) -> bool {
+        self.lock.load(Ordering::Relaxed) & EXCLUSIVE == EXCLUSIVE
+    }
+}
+
+impl<const MAX: usize> Default for RawOneShotRwLockCapped<MAX> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<const MAX: usize> RawRwLockFair for RawOneShotRwLockCapped<MAX> {
+    #[inline]
+    unsafe fn unlock_shared_fair(&self) {
+        unsafe { self.unlock_shared() }
+    }
+
+    #[inline]
+    unsafe fn unlock_exclusive_fair(&self) {
+        unsafe { self.unlock_exclusive() }
+    }
+
+    #[inline]
+    unsafe fn bump_shared(&self) {}
+
+    #[inline]
+    unsafe fn bump_exclusive(&self) {}
+}
+
+unsafe impl<const MAX: usize> RawRwLockRecursive for RawOneShotRwLockCapped<MAX> {
+    #[inline]
+    #[track_caller]
+    fn lock_shared_recursive(&self) {
+        self.lock_shared();
+    }
+
+    #[inline]
+    fn try_lock_shared_recursive(&self) -> bool {
+        self.try_lock_shared()
+    }
+}
+
+unsafe impl<const MAX: usize> RawRwLockDowngrade for RawOneShotRwLockCapped<MAX> {
+    #[inline]
+    unsafe fn downgrade(&self) {
+        emit(self, LockEvent::Downgrade);
+
+        // Reserve the shared guard for ourselves
+        self.acquire_shared();
+
+        self.release_exclusive();
+    }
+}
+
+unsafe impl<const MAX: usize> RawRwLockUpgrade for RawOneShotRwLockCapped<MAX> {
+    #[inline]
+    #[track_caller]
+    fn lock_upgradable(&self) {
+        if let Err(reason) = self.try_lock_upgradable_with_reason() {
+            contended("lock_upgradable", Self::name(), reason);
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_upgradable(&self) -> bool {
+        self.acquire_upgradable().is_ok()
+    }
+
+    #[inline]
+    unsafe fn unlock_upgradable(&self) {
+        emit(self, LockEvent::UnlockUpgradable);
+        self.release_upgradable();
+    }
+
+    #[inline]
+    #[track_caller]
+    unsafe fn upgrade(&self) {
+        if let Err(value) = self.upgrade_to_exclusive(false) {
+            let reason = if value & UPGRADABLE == 0 {
+                "not locked upgradably"
+            } else {
+                "also locked shared by others"
+            };
+            contended("upgrade", Self::name(), reason);
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    unsafe fn try_upgrade(&self) -> bool {
+        self.try_upgrade_to_exclusive(false)
+    }
+}
+
+/// Since this lock never blocks, any timeout is ignored and the lock is tried exactly once.
+/// Contention is considered a bug, so waiting for the lock to become available would not help.
+#[cfg(feature = "std")]
+unsafe impl<const MAX: usize> RawRwLockTimed for RawOneShotRwLockCapped<MAX> {
+    type Duration = Duration;
+    type Instant = Instant;
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_shared_for(&self, _timeout: Self::Duration) -> bool {
+        self.try_lock_shared()
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_shared_until(&self, _timeout: Self::Instant) -> bool {
+        self.try_lock_shared()
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_exclusive_for(&self, _timeout: Self::Duration) -> bool {
+        self.try_lock_exclusive()
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_exclusive_until(&self, _timeout: Self::Instant) -> bool {
+        self.try_lock_exclusive()
+    }
+}
+
+/// Since this lock never blocks, any timeout is ignored and the lock is tried exactly once.
+#[cfg(feature = "std")]
+unsafe impl<const MAX: usize> RawRwLockUpgradeTimed for RawOneShotRwLockCapped<MAX> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_upgradable_for(&self, _timeout: Self::Duration) -> bool {
+        self.try_lock_upgradable()
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    fn try_lock_upgradable_until(&self, _timeout: Self::Instant) -> bool {
+        self.try_lock_upgradable()
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    unsafe fn try_upgrade_for(&self, _timeout: Self::Duration) -> bool {
+        unsafe { self.try_upgrade() }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", track_caller)]
+    unsafe fn try_upgrade_until(&self, _timeout: Self::Instant) -> bool {
+        unsafe { self.try_upgrade() }
+    }
+}
+
+unsafe impl<const MAX: usize> RawRwLockUpgradeFair for RawOneShotRwLockCapped<MAX> {
+    #[inline]
+    unsafe fn unlock_upgradable_fair(&self) {
+        unsafe { self.unlock_upgradable() }
+    }
+
+    #[inline]
+    unsafe fn bump_upgradable(&self) {}
+}
+
+unsafe impl<const MAX: usize> RawRwLockUpgradeDowngrade for RawOneShotRwLockCapped<MAX> {
+    #[inline]
+    unsafe fn downgrade_upgradable(&self) {
+        emit(self, LockEvent::DowngradeUpgradable);
+
+        self.acquire_shared();
+
+        self.release_upgradable();
+    }
+
+    #[inline]
+    unsafe fn downgrade_to_upgradable(&self) {
+        debug_assert!(self.is_locked_exclusive());
+
+        emit(self, LockEvent::DowngradeToUpgradable);
+
+        // Without an exclusive lock, no owner is set.
+        self.lock
+            .fetch_xor(upgradable() | EXCLUSIVE, Ordering::Release);
+    }
+}
+
+/// A [`lock_api::RwLock`] based on [`RawOneShotRwLock`].
+///
+/// [`new`](lock_api::RwLock::new) is a `const fn`, which makes it usable in `static` initializers and `const` blocks.
+/// Outside of const contexts, the lock can also be created through [`From`]:
+///
+/// ```
+/// use one_shot_mutex::OneShotRwLock;
+///
+/// static X: [OneShotRwLock<i32>; 2] = [const { OneShotRwLock::new(42) }; 2];
+///
+/// let lock: OneShotRwLock<i32> = 42.into();
+/// assert_eq!(*lock.read(), *X[0].read());
+/// ```
+///
+/// [`data_ptr`](lock_api::RwLock::data_ptr) returns a raw pointer to the protected data without locking,
+/// for example, to hand its stable address to C code or hardware.
+/// The caller is responsible for synchronizing accesses through the pointer with the guards of this lock:
+/// reads may only overlap with read guards, and writes may not overlap with any guards.
+///
+/// With the `serde` feature, this lock implements `Serialize` and `Deserialize`.
+/// Serializing locks the lock with shared read access and panics if it is already locked exclusively.
+/// Deserializing creates an unlocked lock.
+pub type OneShotRwLock<T> = lock_api::RwLock<RawOneShotRwLock, T>;
+
+/// A [`lock_api::RwLock`] based on [`RawOneShotRwLockCapped`].
+pub type OneShotRwLockCapped<T, const MAX: usize> =
+    lock_api::RwLock<RawOneShotRwLockCapped<MAX>, T>;
+
+/// A [`lock_api::RwLockReadGuard`] based on [`RawOneShotRwLock`].
+///
+/// The guard can be projected to a part of the protected data with [`map`](lock_api::RwLockReadGuard::map),
+/// which returns a [`MappedOneShotRwLockReadGuard`].
+///
+/// The lock is unlocked when the guard is dropped, see [`OneShotMutexGuard`](crate::OneShotMutexGuard) for pitfalls.
+/// To hold the lock forever, use [`leak_read`].
+pub type OneShotRwLockReadGuard<'a, T> = lock_api::RwLockReadGuard<'a, RawOneShotRwLock, T>;
+
+/// A [`lock_api::RwLockUpgradableReadGuard`] based on [`RawOneShotRwLock`].
+pub type OneShotRwLockUpgradableReadGuard<'a, T> =
+    lock_api::RwLockUpgradableReadGuard<'a, RawOneShotRwLock, T>;
+
+/// A [`lock_api::RwLockWriteGuard`] based on [`RawOneShotRwLock`].
+///
+/// The guard can be projected to a part of the protected data with [`map`](lock_api::RwLockWriteGuard::map),
+/// which returns a [`MappedOneShotRwLockWriteGuard`].
+///
+/// The lock is unlocked when the guard is dropped, see [`OneShotMutexGuard`](crate::OneShotMutexGuard) for pitfalls.
+/// To hold the lock forever, use [`leak_write`].
+///
+/// The guard can be downgraded without releasing the lock in between:
+/// [`downgrade`](lock_api::RwLockWriteGuard::downgrade) returns a [`OneShotRwLockReadGuard`] and
+/// [`downgrade_to_upgradable`](lock_api::RwLockWriteGuard::downgrade_to_upgradable) returns a [`OneShotRwLockUpgradableReadGuard`].
+/// Afterwards, other readers may acquire the lock, but writers still panic.
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{OneShotRwLock, OneShotRwLockWriteGuard};
+///
+/// let lock = OneShotRwLock::new(42);
+/// let mut guard = lock.write();
+/// *guard += 1;
+///
+/// let guard = OneShotRwLockWriteGuard::downgrade(guard);
+/// assert_eq!(*guard, 43);
+/// assert_eq!(*lock.read(), 43);
+/// assert!(lock.try_write().is_none());
+/// ```
+pub type OneShotRwLockWriteGuard<'a, T> = lock_api::RwLockWriteGuard<'a, RawOneShotRwLock, T>;
+
+/// A [`lock_api::MappedRwLockReadGuard`] based on [`RawOneShotRwLock`].
+///
+/// This is returned by [`map`](lock_api::RwLockReadGuard::map) and [`try_map`](lock_api::RwLockReadGuard::try_map).
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{MappedOneShotRwLockReadGuard, OneShotRwLock, OneShotRwLockReadGuard};
+///
+/// let lock = OneShotRwLock::new((1, 2));
+/// let second: MappedOneShotRwLockReadGuard<i32> =
+///     OneShotRwLockReadGuard::map(lock.read(), |pair| &pair.1);
+/// assert_eq!(*second, 2);
+/// ```
+pub type MappedOneShotRwLockReadGuard<'a, T> =
+    lock_api::MappedRwLockReadGuard<'a, RawOneShotRwLock, T>;
+
+/// A [`lock_api::MappedRwLockWriteGuard`] based on [`RawOneShotRwLock`].
+///
+/// This is returned by [`map`](lock_api::RwLockWriteGuard::map) and [`try_map`](lock_api::RwLockWriteGuard::try_map).
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{MappedOneShotRwLockWriteGuard, OneShotRwLock, OneShotRwLockWriteGuard};
+///
+/// let lock = OneShotRwLock::new((1, 2));
+/// let mut second: MappedOneShotRwLockWriteGuard<i32> =
+///     OneShotRwLockWriteGuard::map(lock.write(), |pair| &mut pair.1);
+/// *second += 1;
+/// drop(second);
+///
+/// assert_eq!(*lock.read(), (1, 3));
+/// ```
+pub type MappedOneShotRwLockWriteGuard<'a, T> =
+    lock_api::MappedRwLockWriteGuard<'a, RawOneShotRwLock, T>;
+
+/// A [`lock_api::ArcRwLockReadGuard`] based on [`RawOneShotRwLock`].
+#[cfg(feature = "arc_lock")]
+pub type ArcOneShotRwLockReadGuard<T> = lock_api::ArcRwLockReadGuard<RawOneShotRwLock, T>;
+
+/// A [`lock_api::ArcRwLockUpgradableReadGuard`] based on [`RawOneShotRwLock`].
+#[cfg(feature = "arc_lock")]
+pub type ArcOneShotRwLockUpgradableReadGuard<T> =
+    lock_api::ArcRwLockUpgradableReadGuard<RawOneShotRwLock, T>;
+
+/// A [`lock_api::ArcRwLockWriteGuard`] based on [`RawOneShotRwLock`].
+#[cfg(feature = "arc_lock")]
+pub type ArcOneShotRwLockWriteGuard<T> = lock_api::ArcRwLockWriteGuard<RawOneShotRwLock, T>;
+
+/// Leaks a read guard, keeping the lock locked shared forever.
+///
+/// This mirrors [`MutexGuard::leak`](lock_api::MutexGuard::leak), which `lock_api` does not provide for readers-writer locks.
+/// Afterwards, other readers may still acquire the lock, but writers always fail.
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{leak_read, OneShotRwLock};
+///
+/// static TABLE: OneShotRwLock<[u32; 4]> = OneShotRwLock::new([1, 2, 3, 4]);
+///
+/// let table: &'static [u32; 4] = leak_read(TABLE.read());
+/// assert_eq!(table, &*TABLE.read());
+/// assert!(TABLE.try_write().is_none());
+/// ```
+#[inline]
+pub fn leak_read<'a, T: ?Sized>(guard: OneShotRwLockReadGuard<'a, T>) -> &'a T {
+    let lock = lock_api::RwLockReadGuard::rwlock(&guard);
+    mem::forget(guard);
+    // SAFETY: The lock stays locked shared forever, so no writer can access the data anymore.
+    unsafe { &*lock.data_ptr() }
+}
+
+/// Leaks a write guard, keeping the lock locked exclusively forever.
+///
+/// This mirrors [`MutexGuard::leak`](lock_api::MutexGuard::leak), which `lock_api` does not provide for readers-writer locks.
+/// Afterwards, all attempts to acquire the lock fail.
+///
+/// # Examples
+///
+/// ```
+/// use one_shot_mutex::{leak_write, OneShotRwLock};
+///
+/// static REGISTERS: OneShotRwLock<[u32; 4]> = OneShotRwLock::new([0; 4]);
+///
+/// let registers: &'static mut [u32; 4] = leak_write(REGISTERS.write());
+/// registers[0] = 1;
+/// assert!(REGISTERS.try_read().is_none());
+/// ```
+#[inline]
+pub fn leak_write<'a, T: ?Sized>(guard: OneShotRwLockWriteGuard<'a, T>) -> &'a mut T {
+    let lock = lock_api::RwLockWriteGuard::rwlock(&guard);
+    mem::forget(guard);
+    // SAFETY: The lock stays locked exclusively forever, so nobody else can access the data anymore.
+    unsafe { &mut *lock.data_ptr() }
+}
+
+/// Extension methods for [`OneShotRwLock`] and [`OneShotRwLockCapped`].
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait OneShotRwLockExt<T: ?Sized, const MAX: usize = { usize::MAX }>: Sealed {
+    /// Locks this lock with exclusive write access and records the time of acquisition.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lock is already locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotRwLock, OneShotRwLockExt};
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// let guard = lock.write_stamped();
+    /// let held_for = guard.acquired_at().elapsed();
+    /// ```
+    #[cfg(feature = "std")]
+    fn write_stamped(
+        &self,
+    ) -> StampedGuard<lock_api::RwLockWriteGuard<'_, RawOneShotRwLockCapped<MAX>, T>>;
+
+    /// Returns statistics about the successful acquisitions of this lock.
+    ///
+    /// See [`RawOneShotRwLockCapped::access_stats`].
+    #[cfg(feature = "metrics")]
+    fn access_stats(&self) -> AccessStats;
+
+    /// Attempts to lock this lock with shared read access, returning [`Contended`] if it is already locked exclusively.
+    ///
+    /// This is like [`try_read`], but allows propagating contention with `?`.
+    ///
+    /// [`try_read`]: lock_api::RwLock::try_read
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the lock is already locked exclusively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotRwLock, OneShotRwLockExt};
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// let guard = lock.checked_read().unwrap();
+    /// assert!(lock.checked_read().is_ok());
+    /// assert!(lock.checked_write().is_err());
+    /// ```
+    fn checked_read(
+        &self,
+    ) -> Result<lock_api::RwLockReadGuard<'_, RawOneShotRwLockCapped<MAX>, T>, Contended>;
+
+    /// Attempts to lock this lock with exclusive write access, returning [`Contended`] if it is already locked.
+    ///
+    /// This is like [`try_write`], but allows propagating contention with `?`.
+    ///
+    /// [`try_write`]: lock_api::RwLock::try_write
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the lock is already locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{Contended, OneShotRwLock, OneShotRwLockExt};
+    ///
+    /// fn increment(lock: &OneShotRwLock<i32>) -> Result<(), Contended> {
+    ///     *lock.checked_write()? += 1;
+    ///     Ok(())
+    /// }
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// increment(&lock).unwrap();
+    ///
+    /// let guard = lock.read();
+    /// assert!(increment(&lock).is_err());
+    /// ```
+    fn checked_write(
+        &self,
+    ) -> Result<lock_api::RwLockWriteGuard<'_, RawOneShotRwLockCapped<MAX>, T>, Contended>;
+
+    /// Locks this lock with shared read access, calls `f` with the protected data, and releases the lock.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lock is already locked exclusively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotRwLock, OneShotRwLockExt};
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// assert_eq!(lock.with_read(|x| *x), 42);
+    /// assert!(!lock.is_locked());
+    /// ```
+    fn with_read<R>(&self, f: impl FnOnce(&T) -> R) -> R;
+
+    /// Locks this lock with exclusive write access, calls `f` with the protected data, and releases the lock.
+    ///
+    /// This keeps the lock held only for the duration of `f`,
+    /// which prevents accidentally holding the guard for longer and causing contention later.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the lock is already locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotRwLock, OneShotRwLockExt};
+    ///
+    /// let lock = OneShotRwLock::new(42);
+    /// lock.with_write(|x| *x += 1);
+    /// assert_eq!(*lock.write(), 43);
+    /// ```
+    fn with_write<R>(&self, f: impl FnOnce(&mut T) -> R) -> R;
+
+    /// Replaces the protected data, returning the old value.
+    ///
+    /// Since this call borrows the lock mutably, no locking is needed.
+    /// This allows recycling a lock, for example, in an object pool.
+    ///
+    /// In debug builds, this asserts that the lock is unlocked, which only fails if a guard has been leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotRwLock, OneShotRwLockExt};
+    ///
+    /// let mut lock = OneShotRwLock::new(vec![1, 2, 3]);
+    /// let old = lock.replace(Vec::new());
+    /// assert_eq!(old, [1, 2, 3]);
+    /// assert!(lock.read().is_empty());
+    /// ```
+    fn replace(&mut self, value: T) -> T
+    where
+        T: Sized;
+
+    /// Resets this lock to a new unlocked lock protecting `value`.
+    ///
+    /// Since this call borrows the lock mutably, no locking is needed.
+    /// This allows reinitializing a table of locks in place, for example, after a soft reset.
+    ///
+    /// In debug builds, this asserts that the lock is neither locked shared, upgradably, nor exclusively,
+    /// which only fails if a guard has been leaked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use one_shot_mutex::{OneShotRwLock, OneShotRwLockExt};
+    ///
+    /// let mut table = [const { OneShotRwLock::new(0) }; 4];
+    /// *table[1].write() = 42;
+    ///
+    /// for lock in &mut table {
+    ///     lock.reset(0);
+    /// }
+    /// assert_eq!(*table[1].read(), 0);
+    /// ```
+    fn reset(&mut self, value: T)
+    where
+        T: Sized;
+}
+
+impl<T: ?Sized, const MAX: usize> Sealed for OneShotRwLockCapped<T, MAX> {}
+
+impl<T: ?Sized, const MAX: usize> OneShotRwLockExt<T, MAX> for OneShotRwLockCapped<T, MAX> {
+    #[cfg(feature = "std")]
+    #[inline]
+    #[track_caller]
+    fn write_stamped(
+        &self,
+    ) -> StampedGuard<lock_api::RwLockWriteGuard<'_, RawOneShotRwLockCapped<MAX>, T>> {
+        StampedGuard::new(self.write())
+    }
+
+    #[cfg(feature = "metrics")]
+    #[inline]
+    fn access_stats(&self) -> AccessStats {
+        // SAFETY: We do not unlock the lock.
+        unsafe { self.raw() }.access_stats()
+    }
+
+    #[inline]
+    fn checked_read(
+        &self,
+    ) -> Result<lock_api::RwLockReadGuard<'_, RawOneShotRwLockCapped<MAX>, T>, Contended> {
+        self.try_read().ok_or(Contended::new())
+    }
+
+    #[inline]
+    fn checked_write(
+        &self,
+    ) -> Result<lock_api::RwLockWriteGuard<'_, RawOneShotRwLockCapped<MAX>, T>, Contended> {
+        self.try_write().ok_or(Contended::new())
+    }
+
+    #[inline]
+    #[track_caller]
+    fn with_read<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.read())
+    }
+
+    #[inline]
+    #[track_caller]
+    fn with_write<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.write())
+    }
+
+    #[inline]
+    fn replace(&mut self, value: T) -> T
+    where
+        T: Sized,
+    {
+        // SAFETY: We do not lock or unlock the lock.
+        let raw = unsafe { self.raw() };
+        debug_assert!(!raw.is_locked(), "replace a locked {raw:?}");
+        mem::replace(self.get_mut(), value)
+    }
+
+    #[inline]
+    fn reset(&mut self, value: T)
+    where
+        T: Sized,
+    {
+        // SAFETY: We do not lock or unlock the lock.
+        let raw = unsafe { self.raw() };
+        debug_assert!(!raw.is_locked(), "reset a locked {raw:?}");
+        *self = Self::new(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "arc_lock")]
+    use lock_api::ArcRwLockUpgradableReadGuard;
+    use lock_api::RwLockUpgradableReadGuard;
+
+    use super::*;
+
+    #[test]
+    fn lock_exclusive() {
+        let lock = OneShotRwLock::new(42);
+        let mut guard = lock.write();
+        assert_eq!(*guard, 42);
+
+        *guard += 1;
+        drop(guard);
+        let guard = lock.write();
+        assert_eq!(*guard, 43);
+    }
+
+    #[test]
+    #[should_panic]
+    fn lock_exclusive_panic() {
+        let lock = OneShotRwLock::new(42);
+        let _guard = lock.write();
+        let _guard2 = lock.write();
+    }
+
+    #[test]
+    #[should_panic]
+    fn lock_exclusive_shared_panic() {
+        let lock = OneShotRwLock::new(42);
+        let _guard = lock.write();
+        let _guard2 = lock.read();
+    }
+
+    #[test]
+    fn try_lock_exclusive() {
+        let lock = OneShotRwLock::new(42);
+        let mut guard = lock.try_write().unwrap();
+        assert_eq!(*guard, 42);
+        assert!(lock.try_write().is_none());
+
+        *guard += 1;
+        drop(guard);
+        let guard = lock.try_write().unwrap();
+        assert_eq!(*guard, 43);
+    }
+
+    #[test]
+    fn construct() {
+        static STATIC: OneShotRwLock<i32> = OneShotRwLock::new(42);
+        static ARRAY: [OneShotRwLock<i32>; 2] = [const { OneShotRwLock::new(42) }; 2];
+        static CAPPED: OneShotRwLockCapped<i32, 2> = OneShotRwLockCapped::new(42);
+
+        let from: OneShotRwLock<i32> = 42.into();
+        let capped_from: OneShotRwLockCapped<i32, 2> = 42.into();
+        assert_eq!(*STATIC.read(), 42);
+        assert_eq!(*ARRAY[1].read(), 42);
+        assert_eq!(*CAPPED.read(), 42);
+        assert_eq!(*from.read(), 42);
+        assert_eq!(*capped_from.read(), 42);
+    }
+
+    #[test]
+    fn lock_exclusive_unchecked() {
+        let lock = OneShotRwLock::new(42);
+        let guard = unsafe {
+            lock.raw().lock_exclusive_unchecked();
+            lock.make_write_guard_unchecked()
+        };
+        assert_eq!(*guard, 42);
+        assert!(lock.is_locked_exclusive());
+        drop(guard);
+        assert!(!lock.is_locked());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "called `lock_exclusive_unchecked` on a `RawOneShotRwLock` that is already locked"]
+    fn lock_exclusive_unchecked_panic() {
+        let lock = OneShotRwLock::new(42);
+        let _guard = lock.read();
+        unsafe { lock.raw().lock_exclusive_unchecked() }
+    }
+
+    #[test]
+    fn data_ptr() {
+        let lock = OneShotRwLock::new(42);
+        let ptr = lock.data_ptr();
+        assert_eq!(unsafe { ptr.read() }, 42);
+        assert!(!lock.is_locked());
+
+        unsafe { ptr.write(43) }
+        let guard = lock.read();
+        assert_eq!(ptr.cast_const(), &raw const *guard);
+        assert_eq!(unsafe { ptr.read() }, 43);
+        drop(guard);
+
+        let capped = OneShotRwLockCapped::<_, 2>::new(42);
+        assert_eq!(unsafe { capped.data_ptr().read() }, 42);
+    }
+
+    #[test]
+    fn leak_read() {
+        static LOCK: OneShotRwLock<i32> = OneShotRwLock::new(42);
+
+        let x: &'static i32 = super::leak_read(LOCK.read());
+        assert!(LOCK.is_locked());
+        assert_eq!(unsafe { LOCK.raw() }.reader_count(), 1);
+        assert_eq!(*LOCK.read(), *x);
+        assert!(LOCK.try_upgradable_read().is_some());
+        assert!(LOCK.try_write().is_none());
+    }
+
+    #[test]
+    fn leak_write() {
+        static LOCK: OneShotRwLock<i32> = OneShotRwLock::new(42);
+
+        let x: &'static mut i32 = super::leak_write(LOCK.write());
+        *x += 1;
+        assert!(LOCK.is_locked_exclusive());
+        assert!(LOCK.try_read().is_none());
+        assert!(LOCK.try_upgradable_read().is_none());
+        assert!(LOCK.try_write().is_none());
+        assert_eq!(*x, 43);
+    }
+
+    #[test]
+    fn map() {
+        let lock = OneShotRwLock::new((1u8, 2u8));
+        let read: MappedOneShotRwLockReadGuard<u8> =
+            OneShotRwLockReadGuard::map(lock.read(), |pair| &pair.1);
+        assert_eq!(*read, 2);
+        assert!(lock.try_write().is_none());
+        drop(read);
+
+        let mut write: MappedOneShotRwLockWriteGuard<u8> =
+            OneShotRwLockWriteGuard::map(lock.write(), |pair| &mut pair.0);
+        *write += 1;
+        assert!(lock.try_read().is_none());
+        drop(write);
+
+        assert_eq!(*lock.read(), (2, 2));
+    }
+
+    #[test]
+    fn downgrade() {
+        let lock = OneShotRwLock::new(42);
+        let guard = OneShotRwLockWriteGuard::downgrade(lock.write());
+        assert!(lock.is_locked());
+        assert!(!lock.is_locked_exclusive());
+        assert_eq!(*lock.read(), 42);
+        assert!(lock.try_upgradable_read().is_some());
+        assert!(lock.try_write().is_none());
+        drop(guard);
+        assert!(!lock.is_locked());
+    }
+
+    #[test]
+    #[should_panic = "called `lock_exclusive` on a `RawOneShotRwLock` that is already locked"]
+    fn downgrade_write_panic() {
+        let lock = OneShotRwLock::new(42);
+        let _guard = OneShotRwLockWriteGuard::downgrade(lock.write());
+        let _guard2 = lock.write();
+    }
+
+    #[test]
+    fn downgrade_to_upgradable() {
+        let lock = OneShotRwLock::new(42);
+        let guard = OneShotRwLockWriteGuard::downgrade_to_upgradable(lock.write());
+        assert!(lock.is_locked());
+        assert!(!lock.is_locked_exclusive());
+        assert_eq!(*lock.read(), 42);
+        assert!(lock.try_upgradable_read().is_none());
+        assert!(lock.try_write().is_none());
+
+        let guard = OneShotRwLockUpgradableReadGuard::upgrade(guard);
+        assert!(lock.is_locked_exclusive());
+        drop(guard);
+        assert!(!lock.is_locked());
+    }
+
+    #[test]
+    #[should_panic = "called `lock_exclusive` on a `RawOneShotRwLock` that is already locked"]
+    fn downgrade_to_upgradable_write_panic() {
+        let lock = OneShotRwLock::new(42);
+        let _guard = OneShotRwLockWriteGuard::downgrade_to_upgradable(lock.write());
+        let _guard2 = lock.write();
+    }
+
+    #[test]
+    fn try_lock_exclusive_weak() {
+        let lock = RawOneShotRwLock::new();
+
+        // Spurious failures must not prevent eventual success on a single thread.
+        while !lock.try_lock_exclusive_weak() {}
+        assert!(!lock.try_lock_exclusive_weak());
+        assert!(!lock.try_lock_shared());
+
+        unsafe { lock.downgrade_to_upgradable() }
+        lock.lock_shared();
+        assert!(!unsafe { lock.try_upgrade_weak() });
+        unsafe { lock.unlock_shared() }
+        while !unsafe { lock.try_upgrade_weak() } {}
+        assert!(lock.is_locked_exclusive());
+    }
+
+    #[test]
+    #[cfg(feature = "arc_lock")]
+    fn arc_lock() {
+        use std::sync::Arc;
+
+        let lock = Arc::new(OneShotRwLock::new(42));
+        let guard: ArcOneShotRwLockReadGuard<i32> = lock.read_arc();
+        let guard2: ArcOneShotRwLockUpgradableReadGuard<i32> = lock.upgradable_read_arc();
+        assert_eq!(*guard + *guard2, 84);
+        assert!(lock.try_write_arc().is_none());
+        drop(guard);
+
+        let mut guard: ArcOneShotRwLockWriteGuard<i32> =
+            ArcRwLockUpgradableReadGuard::upgrade(guard2);
+        *guard += 1;
+        assert!(lock.try_read_arc().is_none());
+        drop(guard);
+        assert_eq!(*lock.write_arc(), 43);
+    }
+
+    #[test]
+    fn lock_shared() {
+        let lock = OneShotRwLock::new(42);
+        let guard = lock.read();
+        assert_eq!(*guard, 42);
+        let guard2 = lock.read();
+        assert_eq!(*guard2, 42);
+    }
+
+    #[test]
+    #[should_panic]
+    fn lock_shared_panic() {
+        let lock = OneShotRwLock::new(42);
+        let _guard = lock.write();
+        let _guard2 = lock.read();
+    }
+
+    #[test]
+    fn try_lock_shared() {
+        let lock = OneShotRwLock::new(42);
+        let guard = lock.try_read().unwrap();
+        assert_eq!(*guard, 42);
+        assert!(lock.try_write().is_none());
+
+        let guard2 = lock.try_read().unwrap();
+        assert_eq!(*guard2, 42);
+    }
+
+    #[test]
+    fn try_lock_shared_exclusive() {
+        let lock = RawOneShotRwLock::INIT;
+        assert!(lock.try_lock_exclusive());
+        assert!(!lock.try_lock_shared());
+        assert_eq!(lock.lock.load(Ordering::Relaxed), EXCLUSIVE);
+
+        unsafe {
+            lock.unlock_exclusive();
+        }
+        assert!(lock.try_lock_shared());
+        assert_eq!(lock.lock.load(Ordering::Relaxed), SHARED);
+    }
+
+    #[test]
+    fn lock_upgradable() {
+        let lock = OneShotRwLock::new(42);
+        let guard = lock.upgradable_read();
+        assert_eq!(*guard, 42);
+        assert!(lock.try_write().is_none());
+
+        let mut upgraded = RwLockUpgradableReadGuard::upgrade(guard);
+        *upgraded += 1;
+        drop(upgraded);
         let guard2 = lock.upgradable_read();
         assert_eq!(*guard2, 43);
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic = "called `lock_upgradable` on a `RawOneShotRwLock` that is already locked upgradably (is the upgradable guard held recursively?)"]
     fn lock_upgradable_panic() {
         let lock = OneShotRwLock::new(42);
         let _guard = lock.upgradable_read();
@@ -342,13 +1876,29 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
+    #[should_panic = "called `lock_upgradable` on a `RawOneShotRwLock` that is already locked upgradably and shared"]
+    fn lock_upgradable_shared_panic() {
+        let lock = OneShotRwLock::new(42);
+        let _guard = lock.upgradable_read();
+        let _guard2 = lock.read();
+        let _guard3 = lock.upgradable_read();
+    }
+
+    #[test]
+    #[should_panic = "called `lock_upgradable` on a `RawOneShotRwLock` that is already locked exclusively"]
     fn lock_upgradable_write_panic() {
         let lock = OneShotRwLock::new(42);
         let _guard = lock.write();
         let _guard2 = lock.upgradable_read();
     }
 
+    #[test]
+    fn lock_upgradable_read() {
+        let lock = OneShotRwLock::new(42);
+        let _guard = lock.read();
+        let _guard2 = lock.upgradable_read();
+    }
+
     #[test]
     fn try_lock_upgradable() {
         let lock = OneShotRwLock::new(42);
@@ -364,11 +1914,723 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
+    #[cfg(feature = "std")]
+    fn write_stamped() {
+        use std::time::Instant;
+
+        let lock = OneShotRwLock::new(42);
+        let before = Instant::now();
+        let guard = lock.write_stamped();
+        assert_eq!(*guard, 42);
+        assert!(before <= guard.acquired_at());
+        assert!(guard.acquired_at() <= Instant::now());
+        assert!(lock.try_read().is_none());
+
+        let acquired_at = guard.acquired_at();
+        drop(guard);
+        let guard = lock.write_stamped();
+        assert!(acquired_at <= guard.acquired_at());
+    }
+
+    #[test]
+    fn lock_shared_capped() {
+        let lock = OneShotRwLockCapped::<_, 2>::new(42);
+        let guard = lock.read();
+        let guard2 = lock.read();
+        assert!(lock.try_read().is_none());
+        assert_eq!(
+            unsafe { lock.raw() }.lock.load(Ordering::Relaxed),
+            2 * SHARED
+        );
+
+        drop(guard);
+        let _guard3 = lock.read();
+        drop(guard2);
+
+        let _upgradable = lock.upgradable_read();
+        let _guard4 = lock.read();
+        assert!(lock.try_read().is_none());
+    }
+
+    #[test]
+    #[should_panic = "called `lock_shared` on a `RawOneShotRwLockCapped<2>` that is already locked shared by the maximum number of readers"]
+    fn lock_shared_capped_panic() {
+        let lock = OneShotRwLockCapped::<_, 2>::new(42);
+        let _guard = lock.read();
+        let _guard2 = lock.read();
+        let _guard3 = lock.read();
+    }
+
+    #[test]
+    fn capped_ext() {
+        use std::format;
+
+        let mut lock = OneShotRwLockCapped::<_, 2>::new(42);
+        assert_eq!(
+            format!("{:?}", unsafe { lock.raw() }),
+            "RawOneShotRwLockCapped<2> { exclusive: false, upgradable: false, shared: 0 }"
+        );
+
+        assert_eq!(*lock.checked_read().unwrap(), 42);
+        lock.with_write(|value| *value += 1);
+        assert_eq!(lock.replace(1), 43);
+    }
+
+    #[test]
+    #[cfg(feature = "metrics")]
+    fn access_stats() {
+        let lock = OneShotRwLock::new(42);
+        assert_eq!(lock.access_stats(), AccessStats::default());
+
+        let guard = lock.read();
+        let guard2 = lock.read();
+        assert!(lock.try_write().is_none());
+        drop((guard, guard2));
+        assert_eq!(
+            lock.access_stats(),
+            AccessStats {
+                reads: 2,
+                ..Default::default()
+            }
+        );
+
+        drop(lock.write());
+        assert_eq!(lock.access_stats().writes, 1);
+
+        let guard = lock.upgradable_read();
+        assert!(lock.try_upgradable_read().is_none());
+        assert_eq!(lock.access_stats().upgradable_reads, 1);
+
+        let guard = RwLockUpgradableReadGuard::upgrade(guard);
+        drop(guard);
+        assert_eq!(
+            lock.access_stats(),
+            AccessStats {
+                reads: 2,
+                writes: 1,
+                upgradable_reads: 1,
+                upgrades: 1,
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "robust")]
+    fn try_lock_exclusive_reclaiming() {
+        let lock = RawOneShotRwLock::INIT;
+        assert!(lock.try_lock_exclusive_reclaiming(|_| true));
+        unsafe { lock.unlock_exclusive() }
+
+        lock.lock_upgradable();
+        assert_eq!(lock.upgradable_owner(), Some(OwnerId::current()));
+        assert!(!lock.try_lock_exclusive_reclaiming(|_| true));
+
+        lock.lock_shared();
+        assert!(!lock.try_lock_exclusive_reclaiming(|_| false));
+        assert_eq!(lock.upgradable_owner(), Some(OwnerId::current()));
+        unsafe { lock.unlock_shared() }
+
+        assert!(lock.try_lock_exclusive_reclaiming(|owner| owner != OwnerId::current()));
+        assert!(lock.is_locked_exclusive());
+        assert!(lock.upgradable_owner().is_none());
+
+        unsafe { lock.unlock_exclusive() }
+        assert!(!lock.is_locked());
+
+        // The upgradable lock must not be reclaimed if it changes hands while checking its owner.
+        lock.lock_upgradable();
+        let reclaimed = lock.try_lock_exclusive_reclaiming(|_| {
+            unsafe { lock.unlock_upgradable() }
+            lock.lock_shared();
+            false
+        });
+        assert!(!reclaimed);
+        assert_eq!(lock.reader_count(), 1);
+        assert!(lock.upgradable_owner().is_none());
+        unsafe { lock.unlock_shared() }
+
+        lock.lock_upgradable();
+        unsafe { lock.upgrade() }
+        assert!(lock.upgradable_owner().is_none());
+        unsafe { lock.downgrade_to_upgradable() }
+        assert_eq!(lock.upgradable_owner(), Some(OwnerId::current()));
+        unsafe { lock.unlock_upgradable() }
+        assert!(lock.upgradable_owner().is_none());
+    }
+
+    #[test]
+    fn is_locked_acquire() {
+        let lock = RawOneShotRwLock::INIT;
+        assert!(!lock.is_locked_acquire());
+        assert!(!lock.is_locked_exclusive_acquire());
+
+        lock.lock_shared();
+        assert!(lock.is_locked_acquire());
+        assert!(!lock.is_locked_exclusive_acquire());
+        unsafe { lock.unlock_shared() }
+
+        lock.lock_exclusive();
+        assert!(lock.is_locked_acquire());
+        assert!(lock.is_locked_exclusive_acquire());
+        unsafe { lock.unlock_exclusive() }
+        assert!(!lock.is_locked_acquire());
+    }
+
+    #[test]
+    fn force_unlock() {
+        let lock = RawOneShotRwLock::INIT;
+        unsafe {
+            lock.force_unlock_exclusive();
+            lock.force_unlock_shared();
+        }
+        assert!(!lock.is_locked());
+
+        lock.lock_exclusive();
+        unsafe { lock.force_unlock_exclusive() }
+        assert!(!lock.is_locked());
+
+        lock.lock_upgradable();
+        lock.lock_shared();
+        lock.lock_shared();
+        unsafe { lock.force_unlock_shared() }
+        assert!(!lock.is_locked_shared());
+        assert!(lock.is_locked_upgradable());
+        assert!(!lock.try_lock_upgradable());
+
+        unsafe { lock.unlock_upgradable() }
+        assert!(lock.try_lock_exclusive());
+    }
+
+    #[test]
+    fn checked() {
+        let lock = OneShotRwLock::new(42);
+        let mut guard = lock.checked_write().unwrap();
+        assert_eq!(lock.checked_read().unwrap_err(), Contended::new());
+        assert_eq!(lock.checked_write().unwrap_err(), Contended::new());
+
+        *guard += 1;
+        drop(guard);
+        let guard = lock.checked_read().unwrap();
+        assert_eq!(*lock.checked_read().unwrap(), 43);
+        assert!(lock.checked_write().is_err());
+        drop(guard);
+    }
+
+    #[test]
+    fn unlock_fair() {
+        use lock_api::{RwLockReadGuard, RwLockWriteGuard};
+
+        let lock = OneShotRwLock::new(42);
+        let mut guard = lock.write();
+        RwLockWriteGuard::bump(&mut guard);
+        RwLockWriteGuard::unlock_fair(guard);
+        assert!(!lock.is_locked());
+
+        let mut guard = lock.read();
+        RwLockReadGuard::bump(&mut guard);
+        RwLockReadGuard::unlock_fair(guard);
+        assert!(!lock.is_locked());
+
+        let mut guard = lock.upgradable_read();
+        RwLockUpgradableReadGuard::bump(&mut guard);
+        RwLockUpgradableReadGuard::unlock_fair(guard);
+        assert!(!lock.is_locked());
+    }
+
+    #[test]
+    fn default() {
+        use std::vec::Vec;
+
+        #[derive(Default)]
+        struct State {
+            buf: OneShotRwLock<Vec<u8>>,
+            capped: OneShotRwLockCapped<i32, 2>,
+        }
+
+        let lock = OneShotRwLock::<Vec<u8>>::default();
+        assert!(!lock.is_locked());
+        assert_eq!(*lock.read(), Vec::<u8>::default());
+
+        let lock = OneShotRwLockCapped::<i32, 2>::default();
+        assert!(!lock.is_locked());
+        assert_eq!(*lock.write(), i32::default());
+
+        let state = State::default();
+        assert!(state.buf.read().is_empty());
+        assert_eq!(*state.capped.read(), 0);
+    }
+
+    #[test]
+    fn with() {
+        let lock = OneShotRwLock::new(42);
+        lock.with_write(|x| *x += 1);
+        let guard = lock.write();
+        assert_eq!(*guard, 43);
+        drop(guard);
+
+        let x = lock.with_read(|x| {
+            assert!(lock.try_write().is_none());
+            *x
+        });
+        assert_eq!(x, 43);
+        let _guard = lock.write();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn timed() {
+        let lock = OneShotRwLock::new(42);
+        let guard = lock.try_write_for(Duration::from_secs(1)).unwrap();
+        assert!(lock.try_read_for(Duration::MAX).is_none());
+        assert!(lock.try_read_until(Instant::now()).is_none());
+        assert!(lock.try_upgradable_read_for(Duration::ZERO).is_none());
+        drop(guard);
+
+        let guard = lock.try_upgradable_read_until(Instant::now()).unwrap();
+        let guard2 = lock.try_read_for(Duration::ZERO).unwrap();
+        assert!(lock.try_write_until(Instant::now()).is_none());
+        let guard = RwLockUpgradableReadGuard::try_upgrade_for(guard, Duration::MAX).unwrap_err();
+        drop(guard2);
+        let guard = RwLockUpgradableReadGuard::try_upgrade_until(guard, Instant::now()).unwrap();
+        assert!(lock.try_write_for(Duration::ZERO).is_none());
+        drop(guard);
+    }
+
+    #[test]
+    fn reader_count() {
+        let lock = RawOneShotRwLock::INIT;
+        assert_eq!(lock.reader_count(), 0);
+        assert!(!lock.is_locked_upgradable());
+
+        lock.lock_shared();
+        lock.lock_upgradable();
+        lock.lock_shared();
+        assert_eq!(lock.reader_count(), 2);
+        assert!(lock.is_locked_upgradable());
+
+        unsafe {
+            lock.unlock_shared();
+            lock.unlock_shared();
+        }
+        assert_eq!(lock.reader_count(), 0);
+
+        unsafe { lock.upgrade() }
+        assert_eq!(lock.reader_count(), 0);
+        assert!(!lock.is_locked_upgradable());
+    }
+
+    #[test]
+    fn reset() {
+        let mut lock = OneShotRwLock::new(42);
+        *lock.write() += 1;
+        drop(lock.upgradable_read());
+        drop(lock.read());
+        lock.reset(0);
+        assert!(!lock.is_locked());
+        assert_eq!(*lock.write(), 0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "reset a locked RawOneShotRwLock { exclusive: false, upgradable: false, shared: 1 }"]
+    fn reset_shared_panic() {
+        let mut lock = OneShotRwLock::new(42);
+        mem::forget(lock.read());
+        lock.reset(0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "reset a locked RawOneShotRwLock { exclusive: false, upgradable: true, shared: 0 }"]
+    fn reset_upgradable_panic() {
+        let mut lock = OneShotRwLock::new(42);
+        mem::forget(lock.upgradable_read());
+        lock.reset(0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "reset a locked RawOneShotRwLock { exclusive: true, upgradable: false, shared: 0 }"]
+    fn reset_exclusive_panic() {
+        let mut lock = OneShotRwLock::new(42);
+        mem::forget(lock.write());
+        lock.reset(0);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "replace a locked RawOneShotRwLock { exclusive: true, upgradable: false, shared: 0 }"]
+    fn replace_exclusive_panic() {
+        let mut lock = OneShotRwLock::new(42);
+        mem::forget(lock.write());
+        lock.replace(0);
+    }
+
+    #[test]
+    fn replace() {
+        let mut lock = OneShotRwLock::new(42);
+        assert_eq!(lock.replace(43), 42);
+        assert!(!lock.is_locked());
+        assert_eq!(*lock.read(), 43);
+    }
+
+    #[test]
+    fn debug() {
+        use std::format;
+
+        let lock = RawOneShotRwLock::INIT;
+        assert_eq!(
+            format!("{lock:?}"),
+            "RawOneShotRwLock { exclusive: false, upgradable: false, shared: 0 }"
+        );
+
+        lock.lock_upgradable();
+        lock.lock_shared();
+        lock.lock_shared();
+        assert_eq!(
+            format!("{lock:?}"),
+            "RawOneShotRwLock { exclusive: false, upgradable: true, shared: 2 }"
+        );
+
+        unsafe {
+            lock.unlock_shared();
+            lock.unlock_shared();
+            lock.upgrade();
+        }
+        assert_eq!(
+            format!("{lock:?}"),
+            "RawOneShotRwLock { exclusive: true, upgradable: false, shared: 0 }"
+        );
+    }
+
+    #[test]
+    fn try_lock_failure() {
+        let lock = RawOneShotRwLock::INIT;
+
+        lock.lock_exclusive();
+        assert!(!lock.try_lock_shared());
+        assert!(!lock.try_lock_upgradable());
+        assert!(!lock.try_lock_exclusive());
+        assert_eq!(lock.lock.load(Ordering::Relaxed), EXCLUSIVE);
+        unsafe { lock.unlock_exclusive() }
+
+        lock.lock_upgradable();
+        lock.lock_shared();
+        assert!(!lock.try_lock_upgradable());
+        assert!(!lock.try_lock_exclusive());
+        assert!(!unsafe { lock.try_upgrade() });
+        assert_eq!(lock.lock.load(Ordering::Relaxed), upgradable() | SHARED);
+    }
+
+    #[test]
+    fn admission() {
+        fn check<const MAX: usize>(lock: &RawOneShotRwLockCapped<MAX>) {
+            let admission = lock.admission();
+
+            let can_share = lock.try_lock_shared();
+            if can_share {
+                unsafe { lock.unlock_shared() }
+            }
+
+            let can_upgradable = lock.try_lock_upgradable();
+            if can_upgradable {
+                unsafe { lock.unlock_upgradable() }
+            }
+
+            let can_exclusive = lock.try_lock_exclusive_strong();
+            if can_exclusive {
+                unsafe { lock.unlock_exclusive() }
+            }
+
+            let expected = Admission {
+                can_share,
+                can_upgradable,
+                can_exclusive,
+            };
+            assert_eq!(admission, expected);
+        }
+
+        let lock = RawOneShotRwLock::INIT;
+        check(&lock);
+
+        lock.lock_shared();
+        check(&lock);
+
+        lock.lock_upgradable();
+        check(&lock);
+
+        unsafe { lock.unlock_shared() }
+        check(&lock);
+
+        unsafe { lock.upgrade() }
+        check(&lock);
+
+        let lock = RawOneShotRwLockCapped::<1>::INIT;
+        lock.lock_shared();
+        check(&lock);
+    }
+
+    #[test]
+    #[should_panic = "also locked shared by others"]
     fn upgrade_panic() {
         let lock = OneShotRwLock::new(42);
         let guard = lock.upgradable_read();
         let _guard2 = lock.read();
         let _guard3 = RwLockUpgradableReadGuard::upgrade(guard);
     }
+
+    /// The state of a [`RawOneShotRwLockCapped`], independent of its bit representation.
+    #[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+    struct Model {
+        readers: usize,
+        upgradable: bool,
+        exclusive: bool,
+    }
+
+    impl Model {
+        fn admission(self, max: usize) -> Admission {
+            Admission {
+                can_share: !self.exclusive && self.readers < max,
+                can_upgradable: !self.upgradable && !self.exclusive,
+                can_exclusive: self.readers == 0 && !self.upgradable && !self.exclusive,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    enum Op {
+        LockShared,
+        TryLockShared,
+        UnlockShared,
+        LockUpgradable,
+        TryLockUpgradable,
+        UnlockUpgradable,
+        LockExclusive,
+        TryLockExclusive,
+        TryLockExclusiveWeak,
+        TryLockExclusiveStrong,
+        UnlockExclusive,
+        Upgrade,
+        TryUpgrade,
+        TryUpgradeWeak,
+        TryUpgradeStrong,
+        Downgrade,
+        DowngradeToUpgradable,
+        DowngradeUpgradable,
+    }
+
+    impl Op {
+        const ALL: [Self; 18] = [
+            Self::LockShared,
+            Self::TryLockShared,
+            Self::UnlockShared,
+            Self::LockUpgradable,
+            Self::TryLockUpgradable,
+            Self::UnlockUpgradable,
+            Self::LockExclusive,
+            Self::TryLockExclusive,
+            Self::TryLockExclusiveWeak,
+            Self::TryLockExclusiveStrong,
+            Self::UnlockExclusive,
+            Self::Upgrade,
+            Self::TryUpgrade,
+            Self::TryUpgradeWeak,
+            Self::TryUpgradeStrong,
+            Self::Downgrade,
+            Self::DowngradeToUpgradable,
+            Self::DowngradeUpgradable,
+        ];
+
+        /// Applies this operation to `lock` and `model` if it is legal in the state described by `model`.
+        ///
+        /// Operations that would panic or violate the safety requirements of the raw lock are illegal.
+        fn apply<const MAX: usize>(
+            self,
+            lock: &RawOneShotRwLockCapped<MAX>,
+            model: &mut Model,
+        ) -> bool {
+            let admission = model.admission(MAX);
+
+            match self {
+                Self::LockShared if admission.can_share => {
+                    lock.lock_shared();
+                    model.readers += 1;
+                }
+                Self::TryLockShared => {
+                    assert_eq!(lock.try_lock_shared(), admission.can_share);
+                    model.readers += usize::from(admission.can_share);
+                }
+                Self::UnlockShared if model.readers > 0 => {
+                    unsafe { lock.unlock_shared() }
+                    model.readers -= 1;
+                }
+                Self::LockUpgradable if admission.can_upgradable => {
+                    lock.lock_upgradable();
+                    model.upgradable = true;
+                }
+                Self::TryLockUpgradable => {
+                    assert_eq!(lock.try_lock_upgradable(), admission.can_upgradable);
+                    model.upgradable |= admission.can_upgradable;
+                }
+                Self::UnlockUpgradable if model.upgradable => {
+                    unsafe { lock.unlock_upgradable() }
+                    model.upgradable = false;
+                }
+                Self::LockExclusive if admission.can_exclusive => {
+                    lock.lock_exclusive();
+                    model.exclusive = true;
+                }
+                Self::TryLockExclusive => {
+                    let acquired = lock.try_lock_exclusive();
+                    assert_eq!(acquired, admission.can_exclusive);
+                    model.exclusive |= acquired;
+                }
+                Self::TryLockExclusiveWeak => {
+                    // This may fail spuriously, but never succeeds if the model forbids it.
+                    let acquired = lock.try_lock_exclusive_weak();
+                    assert!(!acquired || admission.can_exclusive);
+                    model.exclusive |= acquired;
+                }
+                Self::TryLockExclusiveStrong => {
+                    let acquired = lock.try_lock_exclusive_strong();
+                    assert_eq!(acquired, admission.can_exclusive);
+                    model.exclusive |= acquired;
+                }
+                Self::UnlockExclusive if model.exclusive => {
+                    unsafe { lock.unlock_exclusive() }
+                    model.exclusive = false;
+                }
+                Self::Upgrade if model.upgradable && model.readers == 0 => {
+                    unsafe { lock.upgrade() }
+                    model.upgradable = false;
+                    model.exclusive = true;
+                }
+                Self::TryUpgrade if model.upgradable => {
+                    let upgraded = unsafe { lock.try_upgrade() };
+                    assert_eq!(upgraded, model.readers == 0);
+                    if upgraded {
+                        model.upgradable = false;
+                        model.exclusive = true;
+                    }
+                }
+                Self::TryUpgradeWeak if model.upgradable => {
+                    // This may fail spuriously, but never succeeds if the model forbids it.
+                    let upgraded = unsafe { lock.try_upgrade_weak() };
+                    assert!(!upgraded || model.readers == 0);
+                    if upgraded {
+                        model.upgradable = false;
+                        model.exclusive = true;
+                    }
+                }
+                Self::TryUpgradeStrong if model.upgradable => {
+                    let upgraded = lock.upgrade_to_exclusive(false).is_ok();
+                    assert_eq!(upgraded, model.readers == 0);
+                    if upgraded {
+                        model.upgradable = false;
+                        model.exclusive = true;
+                    }
+                }
+                Self::Downgrade if model.exclusive => {
+                    unsafe { lock.downgrade() }
+                    model.exclusive = false;
+                    model.readers += 1;
+                }
+                Self::DowngradeToUpgradable if model.exclusive => {
+                    unsafe { lock.downgrade_to_upgradable() }
+                    model.exclusive = false;
+                    model.upgradable = true;
+                }
+                Self::DowngradeUpgradable if model.upgradable => {
+                    unsafe { lock.downgrade_upgradable() }
+                    model.upgradable = false;
+                    model.readers += 1;
+                }
+                _ => return false,
+            }
+
+            true
+        }
+    }
+
+    /// Calls every wait-free `try_*` method on `lock` in the state described by `model`.
+    ///
+    /// Nothing else changes the lock on this thread, so a method that retried until it succeeded would never return.
+    /// Each method must instead give up after one attempt, admit exactly what `model` admits, and leave the lock as it was.
+    fn assert_try_bounded<const MAX: usize>(lock: &RawOneShotRwLockCapped<MAX>, model: Model) {
+        let admission = model.admission(MAX);
+        let assert_unchanged = |op| {
+            let context = (op, model);
+            assert_eq!(lock.is_locked_exclusive(), model.exclusive, "{context:?}");
+            assert_eq!(lock.is_locked_upgradable(), model.upgradable, "{context:?}");
+            assert_eq!(lock.reader_count(), model.readers, "{context:?}");
+        };
+
+        let acquired = lock.try_lock_shared();
+        assert_eq!(acquired, admission.can_share);
+        if acquired {
+            unsafe { lock.unlock_shared() }
+        }
+        assert_unchanged("try_lock_shared");
+
+        let acquired = lock.try_lock_upgradable();
+        assert_eq!(acquired, admission.can_upgradable);
+        if acquired {
+            unsafe { lock.unlock_upgradable() }
+        }
+        assert_unchanged("try_lock_upgradable");
+
+        // The weak methods may fail spuriously, but never succeed if the model forbids it.
+        let acquired = lock.try_lock_exclusive_weak();
+        assert!(!acquired || admission.can_exclusive);
+        if acquired {
+            unsafe { lock.unlock_exclusive() }
+        }
+        assert_unchanged("try_lock_exclusive_weak");
+
+        if model.upgradable {
+            let upgraded = unsafe { lock.try_upgrade_weak() };
+            assert!(!upgraded || model.readers == 0);
+            if upgraded {
+                unsafe { lock.downgrade_to_upgradable() }
+            }
+            assert_unchanged("try_upgrade_weak");
+        }
+    }
+
+    /// Drives a fresh lock through every legal sequence of operations up to `depth` operations long.
+    fn explore<const MAX: usize>(path: &mut std::vec::Vec<Op>, depth: usize) {
+        for op in Op::ALL {
+            let lock = RawOneShotRwLockCapped::<MAX>::INIT;
+            let mut model = Model::default();
+            for &op in path.iter() {
+                assert!(op.apply(&lock, &mut model));
+            }
+
+            if !op.apply(&lock, &mut model) {
+                continue;
+            }
+
+            let context = (&path, op, model);
+            assert_eq!(lock.is_locked(), model != Model::default(), "{context:?}");
+            assert_eq!(lock.is_locked_exclusive(), model.exclusive, "{context:?}");
+            assert_eq!(lock.is_locked_upgradable(), model.upgradable, "{context:?}");
+            assert_eq!(lock.reader_count(), model.readers, "{context:?}");
+            assert_eq!(lock.admission(), model.admission(MAX), "{context:?}");
+            assert_try_bounded(&lock, model);
+
+            if depth > 1 {
+                path.push(op);
+                explore::<MAX>(path, depth - 1);
+                path.pop();
+            }
+        }
+    }
+
+    #[test]
+    fn model() {
+        const DEPTH: usize = if cfg!(miri) { 3 } else { 6 };
+
+        explore::<{ usize::MAX }>(&mut std::vec::Vec::new(), DEPTH);
+        explore::<2>(&mut std::vec::Vec::new(), DEPTH);
+    }
 }
@@ -0,0 +1,63 @@
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use std::time::Instant;
+
+/// A guard wrapper that carries the time at which the lock was acquired.
+///
+/// This is returned by [`OneShotMutexExt::lock_stamped`] and [`OneShotRwLockExt::write_stamped`].
+/// Unlike measuring the hold time on drop, [`acquired_at`] allows measuring it at any point while the guard is held.
+///
+/// [`OneShotMutexExt::lock_stamped`]: crate::OneShotMutexExt::lock_stamped
+/// [`OneShotRwLockExt::write_stamped`]: crate::OneShotRwLockExt::write_stamped
+/// [`acquired_at`]: Self::acquired_at
+pub struct StampedGuard<G> {
+    guard: G,
+    acquired_at: Instant,
+}
+
+impl<G> StampedGuard<G> {
+    #[inline]
+    pub(crate) fn new(guard: G) -> Self {
+        Self {
+            guard,
+            acquired_at: Instant::now(),
+        }
+    }
+
+    /// Returns the time at which the lock was acquired.
+    #[inline]
+    pub fn acquired_at(&self) -> Instant {
+        self.acquired_at
+    }
+
+    /// Unwraps the guard.
+    #[inline]
+    pub fn into_inner(self) -> G {
+        self.guard
+    }
+}
+
+impl<G: Deref> Deref for StampedGuard<G> {
+    type Target = G::Target;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<G: DerefMut> DerefMut for StampedGuard<G> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<G: fmt::Debug> fmt::Debug for StampedGuard<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StampedGuard")
+            .field("guard", &self.guard)
+            .field("acquired_at", &self.acquired_at)
+            .finish()
+    }
+}
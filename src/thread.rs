@@ -0,0 +1,18 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The next thread ID to hand out.
+///
+/// [`std::thread::ThreadId::as_u64`] is unstable, so we assign our own IDs.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+std::thread_local! {
+    static ID: usize = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns a non-zero ID that is unique to the current thread.
+///
+/// Returns `0` if the ID is not available because the thread-local storage is being destroyed.
+#[inline]
+pub(crate) fn current_id() -> usize {
+    ID.try_with(|id| *id).unwrap_or(0)
+}
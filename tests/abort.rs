@@ -0,0 +1,34 @@
+use std::env;
+use std::process::Command;
+
+use one_shot_mutex::OneShotMutexAbort;
+
+const CHILD: &str = "ONE_SHOT_MUTEX_ABORT_CHILD";
+
+#[test]
+#[cfg_attr(miri, ignore = "spawns a process")]
+fn lock_abort() {
+    if env::var_os(CHILD).is_some() {
+        let mutex = OneShotMutexAbort::new(42);
+        let _guard = mutex.lock();
+        let _guard2 = mutex.lock();
+        unreachable!();
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["--exact", "lock_abort", "--nocapture", "--test-threads=1"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        assert_eq!(output.status.signal(), Some(6));
+    }
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("called `lock` on a `RawOneShotMutexAbort` that is already locked"));
+}
@@ -0,0 +1,100 @@
+//! Compile-time checks of the `Send` and `Sync` implementations of the locks and their guards.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use one_shot_mutex::{
+    NamedOneShotMutex, NamedOneShotRwLock, OneShotMutex, OneShotMutexAbort, OneShotMutexAbortGuard,
+    OneShotMutexGuard, OneShotRwLock, OneShotRwLockReadGuard, OneShotRwLockUpgradableReadGuard,
+    OneShotRwLockWriteGuard, RawOneShotMutex, RawOneShotRwLock,
+};
+
+/// Asserts that a type implements all of the given traits.
+macro_rules! assert_impl {
+    ($ty:ty: $($traits:path),+) => {
+        const _: fn() = || {
+            fn assert_impl<T: ?Sized $(+ $traits)+>() {}
+            assert_impl::<$ty>();
+        };
+    };
+}
+
+/// Asserts that a type does not implement the given trait.
+///
+/// If the type implements the trait, both impls of `Ambiguous` apply and the type cannot be inferred.
+macro_rules! assert_not_impl {
+    ($ty:ty: $trait:path) => {
+        const _: fn() = || {
+            trait Ambiguous<A> {
+                fn some_item() {}
+            }
+
+            impl<T: ?Sized> Ambiguous<()> for T {}
+
+            struct Invalid;
+            impl<T: ?Sized + $trait> Ambiguous<Invalid> for T {}
+
+            <$ty as Ambiguous<_>>::some_item();
+        };
+    };
+}
+
+assert_impl!(RawOneShotMutex: Send, Sync);
+assert_impl!(RawOneShotRwLock: Send, Sync);
+
+// Like `std::sync::Mutex`, a mutex only requires `T: Send` to be `Sync`.
+assert_impl!(OneShotMutex<Cell<i32>>: Send, Sync);
+assert_not_impl!(OneShotMutex<Rc<i32>>: Send);
+assert_not_impl!(OneShotMutex<Rc<i32>>: Sync);
+assert_impl!(OneShotMutexAbort<Cell<i32>>: Send, Sync);
+assert_impl!(NamedOneShotMutex<Cell<i32>>: Send, Sync);
+
+// Guards may be sent to other threads, since the raw locks use `GuardSend`.
+assert_impl!(OneShotMutexGuard<'static, i32>: Send, Sync);
+assert_impl!(OneShotMutexGuard<'static, Cell<i32>>: Send);
+assert_not_impl!(OneShotMutexGuard<'static, Cell<i32>>: Sync);
+assert_not_impl!(OneShotMutexGuard<'static, Rc<i32>>: Send);
+assert_impl!(OneShotMutexAbortGuard<'static, i32>: Send, Sync);
+
+// Like `std::sync::RwLock`, a readers-writer lock requires `T: Send + Sync` to be `Sync`.
+assert_impl!(OneShotRwLock<i32>: Send, Sync);
+assert_impl!(OneShotRwLock<Cell<i32>>: Send);
+assert_not_impl!(OneShotRwLock<Cell<i32>>: Sync);
+assert_not_impl!(OneShotRwLock<Rc<i32>>: Send);
+assert_impl!(NamedOneShotRwLock<i32>: Send, Sync);
+assert_not_impl!(NamedOneShotRwLock<Cell<i32>>: Sync);
+
+assert_impl!(OneShotRwLockReadGuard<'static, i32>: Send, Sync);
+assert_impl!(OneShotRwLockUpgradableReadGuard<'static, i32>: Send, Sync);
+assert_impl!(OneShotRwLockWriteGuard<'static, i32>: Send, Sync);
+assert_not_impl!(OneShotRwLockReadGuard<'static, Cell<i32>>: Send);
+assert_not_impl!(OneShotRwLockReadGuard<'static, Cell<i32>>: Sync);
+assert_not_impl!(OneShotRwLockWriteGuard<'static, Cell<i32>>: Send);
+assert_not_impl!(OneShotRwLockWriteGuard<'static, Cell<i32>>: Sync);
+
+#[cfg(feature = "critical-section")]
+mod cs {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use one_shot_mutex::CsOneShotMutex;
+
+    // Like `critical_section::Mutex`, only `T: Send` is required to be `Sync`.
+    assert_impl!(CsOneShotMutex<Cell<i32>>: Send, Sync);
+    assert_impl!(CsOneShotMutex<RefCell<i32>>: Send, Sync);
+    assert_not_impl!(CsOneShotMutex<Rc<i32>>: Send);
+    assert_not_impl!(CsOneShotMutex<Rc<i32>>: Sync);
+}
+
+#[cfg(feature = "poison")]
+mod poison {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use one_shot_mutex::{PoisonOneShotMutex, PoisonOneShotMutexGuard};
+
+    assert_impl!(PoisonOneShotMutex<Cell<i32>>: Send, Sync);
+    assert_not_impl!(PoisonOneShotMutex<Rc<i32>>: Sync);
+    assert_impl!(PoisonOneShotMutexGuard<'static, i32>: Send, Sync);
+    assert_not_impl!(PoisonOneShotMutexGuard<'static, Cell<i32>>: Sync);
+}
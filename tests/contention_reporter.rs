@@ -0,0 +1,67 @@
+#![cfg(panic = "unwind")]
+
+use std::panic::{self, AssertUnwindSafe, Location};
+use std::sync::Mutex;
+
+use one_shot_mutex::{
+    set_contention_reporter, ContentionInfo, ContentionReporter, OneShotMutex, OneShotMutexGuard,
+    OneShotRwLock,
+};
+
+struct CapturingReporter {
+    reports: Mutex<Vec<String>>,
+}
+
+impl ContentionReporter for CapturingReporter {
+    fn report(&self, info: &ContentionInfo) {
+        self.reports.lock().unwrap().push(info.to_string());
+    }
+}
+
+static REPORTER: CapturingReporter = CapturingReporter {
+    reports: Mutex::new(Vec::new()),
+};
+
+/// Locks `mutex` and returns the location that the lock records.
+#[track_caller]
+fn lock_at<T>(mutex: &OneShotMutex<T>) -> (OneShotMutexGuard<'_, T>, &'static Location<'static>) {
+    (mutex.lock(), Location::caller())
+}
+
+#[test]
+fn report() {
+    set_contention_reporter(&REPORTER).unwrap();
+    assert!(set_contention_reporter(&REPORTER).is_err());
+
+    let mutex = OneShotMutex::new(42);
+    let (_guard, locked_at) = lock_at(&mutex);
+    let mut mutex_reason = if cfg!(feature = "owner-tracking") {
+        String::from("already locked by the current thread (reentrancy)")
+    } else {
+        String::from("already locked")
+    };
+    if cfg!(feature = "track-location") {
+        mutex_reason += &format!(" at {locked_at}");
+    }
+    assert!(mutex.try_lock().is_none());
+    assert!(REPORTER.reports.lock().unwrap().is_empty());
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| mutex.lock()));
+    assert!(result.is_err());
+
+    let lock = OneShotRwLock::new(42);
+    let _guard = lock.write();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| lock.read()));
+    assert!(result.is_err());
+
+    let reports = REPORTER.reports.lock().unwrap();
+    assert_eq!(
+        *reports,
+        [
+            format!("called `lock` on a `RawOneShotMutex` that is {mutex_reason}"),
+            String::from(
+                "called `lock_shared` on a `RawOneShotRwLock` that is already locked exclusively"
+            ),
+        ]
+    );
+}
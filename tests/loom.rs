@@ -0,0 +1,83 @@
+#![cfg(feature = "loom")]
+
+use loom::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+use loom::sync::Arc;
+use loom::thread;
+use one_shot_mutex::{OneShotMutex, OneShotRwLock};
+
+#[test]
+fn mutex_try_lock() {
+    loom::model(|| {
+        let mutex = Arc::new(OneShotMutex::new(0));
+        assert!(!mutex.is_locked());
+        let acquired = Arc::new(AtomicUsize::new(0));
+
+        let handles = [(); 2].map(|()| {
+            let mutex = mutex.clone();
+            let acquired = acquired.clone();
+            thread::spawn(move || {
+                if let Some(mut guard) = mutex.try_lock() {
+                    *guard += 1;
+                    acquired.fetch_add(1, Ordering::Relaxed);
+                    // Hold the lock until both threads have tried to acquire it.
+                    thread::yield_now();
+                    core::mem::forget(guard);
+                }
+            })
+        });
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(acquired.load(Ordering::Relaxed), 1);
+        assert!(mutex.is_locked());
+    });
+}
+
+#[test]
+fn rwlock_try_write() {
+    loom::model(|| {
+        let lock = Arc::new(OneShotRwLock::new(0));
+        assert!(!lock.is_locked());
+
+        let handle = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.try_write().map(core::mem::forget).is_some())
+        };
+
+        let acquired = lock.try_write().map(core::mem::forget).is_some();
+        assert!(acquired ^ handle.join().unwrap());
+    });
+}
+
+/// The store-buffering pattern from the documentation of `RawOneShotMutex::unlock_seqcst`.
+///
+/// With a `SeqCst` unlock, the two threads cannot both miss each other's store.
+/// loom cannot check this, since it models `SeqCst` loads and stores as `AcqRel` and only supports `SeqCst` fences.
+/// It reports the forbidden outcome with `unlock_seqcst` just as with `unlock`,
+/// so this model cannot tell the two apart and is ignored until loom models `SeqCst` accesses.
+#[test]
+#[ignore = "loom models SeqCst loads and stores as AcqRel"]
+fn mutex_unlock_seqcst_store_buffering() {
+    loom::model(|| {
+        let mutex = Arc::new(OneShotMutex::new(()));
+        core::mem::forget(mutex.lock());
+        let flag = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let mutex = mutex.clone();
+            let flag = flag.clone();
+            thread::spawn(move || {
+                flag.store(true, Ordering::SeqCst);
+                fence(Ordering::SeqCst);
+                !mutex.is_locked()
+            })
+        };
+
+        unsafe { mutex.raw().unlock_seqcst() }
+        let saw_flag = flag.load(Ordering::SeqCst);
+        let saw_unlock = handle.join().unwrap();
+        assert!(saw_flag || saw_unlock);
+    });
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "replay")]
+
+use std::sync::Mutex;
+
+use lock_api::{RwLockUpgradableReadGuard, RwLockWriteGuard};
+use one_shot_mutex::{
+    set_replay_sink, LockEvent, OneShotMutex, OneShotRwLock, ReplayRecord, ReplaySink,
+};
+
+struct RecordingSink {
+    records: Mutex<Vec<ReplayRecord>>,
+}
+
+impl ReplaySink for RecordingSink {
+    fn record(&self, record: ReplayRecord) {
+        self.records.lock().unwrap().push(record);
+    }
+}
+
+static SINK: RecordingSink = RecordingSink {
+    records: Mutex::new(Vec::new()),
+};
+
+#[test]
+fn record() {
+    let mutex = OneShotMutex::new(42);
+    let lock = OneShotRwLock::new(42);
+
+    // Events before setting the sink are not recorded.
+    drop(mutex.lock());
+
+    set_replay_sink(&SINK).unwrap();
+    assert!(set_replay_sink(&SINK).is_err());
+
+    let guard = mutex.lock();
+    assert!(mutex.try_lock().is_none());
+    drop(guard);
+
+    let guard = lock.upgradable_read();
+    let guard = RwLockUpgradableReadGuard::upgrade(guard);
+    let guard = RwLockWriteGuard::downgrade(guard);
+    let guard2 = lock.read();
+    drop(guard);
+    drop(guard2);
+
+    let mutex_addr = std::ptr::from_ref(unsafe { mutex.raw() }).addr();
+    let lock_addr = std::ptr::from_ref(unsafe { lock.raw() }).addr();
+    let expected = [
+        (mutex_addr, LockEvent::Lock),
+        (mutex_addr, LockEvent::Unlock),
+        (lock_addr, LockEvent::LockUpgradable),
+        (lock_addr, LockEvent::Upgrade),
+        (lock_addr, LockEvent::Downgrade),
+        (lock_addr, LockEvent::LockShared),
+        (lock_addr, LockEvent::UnlockShared),
+        (lock_addr, LockEvent::UnlockShared),
+    ];
+
+    let records = SINK.records.lock().unwrap();
+    let records = records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| {
+            assert_eq!(record.seq, i);
+            (record.lock, record.event)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(records, expected);
+}
@@ -0,0 +1,24 @@
+#![cfg(all(feature = "replay", panic = "unwind"))]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use one_shot_mutex::{set_replay_sink, LockEvent, OneShotRwLock, ReplayVerifier};
+
+static VERIFIER: ReplayVerifier =
+    ReplayVerifier::new(&[LockEvent::LockShared, LockEvent::UnlockShared]);
+
+#[test]
+fn divergence() {
+    set_replay_sink(&VERIFIER).unwrap();
+
+    let lock = OneShotRwLock::new(42);
+    drop(lock.read());
+    assert!(VERIFIER.is_complete());
+
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| lock.write())).unwrap_err();
+    let message = payload.downcast::<String>().unwrap();
+    assert!(
+        message.contains("replay diverged at event 2: expected None, got LockExclusive"),
+        "{message}"
+    );
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "serde")]
+
+use one_shot_mutex::{OneShotMutex, OneShotRwLock};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Config {
+    name: OneShotMutex<String>,
+    values: OneShotRwLock<Vec<i32>>,
+}
+
+#[test]
+fn round_trip() {
+    let config = Config {
+        name: OneShotMutex::new(String::from("foo")),
+        values: OneShotRwLock::new(vec![1, 2, 3]),
+    };
+
+    let json = serde_json::to_string(&config).unwrap();
+    assert_eq!(json, r#"{"name":"foo","values":[1,2,3]}"#);
+
+    let config: Config = serde_json::from_str(&json).unwrap();
+    assert!(!config.name.is_locked());
+    assert!(!config.values.is_locked());
+    assert_eq!(*config.name.lock(), "foo");
+    assert_eq!(*config.values.read(), [1, 2, 3]);
+}
+
+#[test]
+fn serialize_read_locked() {
+    let lock = OneShotRwLock::new(42);
+    let _guard = lock.read();
+    assert_eq!(serde_json::to_string(&lock).unwrap(), "42");
+}
+
+#[test]
+#[should_panic]
+fn serialize_locked_panic() {
+    let mutex = OneShotMutex::new(42);
+    let _guard = mutex.lock();
+    let _ = serde_json::to_string(&mutex);
+}
+
+#[test]
+#[should_panic]
+fn serialize_write_locked_panic() {
+    let lock = OneShotRwLock::new(42);
+    let _guard = lock.write();
+    let _ = serde_json::to_string(&lock);
+}
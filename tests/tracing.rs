@@ -0,0 +1,163 @@
+#![cfg(feature = "tracing")]
+
+use std::fmt;
+use std::panic::Location;
+use std::sync::{Arc, Mutex};
+
+use one_shot_mutex::{OneShotMutex, OneShotMutexGuard, OneShotRwLock};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+#[derive(Debug, PartialEq, Eq)]
+struct Captured {
+    level: Level,
+    message: String,
+    event: Option<String>,
+    operation: Option<String>,
+    location: String,
+}
+
+#[derive(Clone, Default)]
+struct CapturingSubscriber {
+    events: Arc<Mutex<Vec<Captured>>>,
+}
+
+struct CapturingVisitor<'a>(&'a mut Captured);
+
+impl Visit for CapturingVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let value = format!("{value:?}");
+        match field.name() {
+            "message" => self.0.message = value,
+            "event" => self.0.event = Some(value),
+            "location" => self.0.location = value,
+            _ => {}
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "operation" {
+            self.0.operation = Some(value.to_owned());
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+}
+
+impl Subscriber for CapturingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut captured = Captured {
+            level: *event.metadata().level(),
+            message: String::new(),
+            event: None,
+            operation: None,
+            location: String::new(),
+        };
+        event.record(&mut CapturingVisitor(&mut captured));
+        self.events.lock().unwrap().push(captured);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+fn capture(f: impl FnOnce()) -> Vec<Captured> {
+    let subscriber = CapturingSubscriber::default();
+    let events = subscriber.events.clone();
+    tracing::subscriber::with_default(subscriber, f);
+    Arc::try_unwrap(events).unwrap().into_inner().unwrap()
+}
+
+/// Locks `mutex` and returns the location that the lock reports.
+#[track_caller]
+fn lock_at<T>(mutex: &OneShotMutex<T>) -> (OneShotMutexGuard<'_, T>, &'static Location<'static>) {
+    (mutex.lock(), Location::caller())
+}
+
+/// Tries to lock `mutex` and returns the location that the lock reports.
+#[track_caller]
+fn try_lock_at<T>(
+    mutex: &OneShotMutex<T>,
+) -> (Option<OneShotMutexGuard<'_, T>>, &'static Location<'static>) {
+    (mutex.try_lock(), Location::caller())
+}
+
+#[test]
+fn mutex() {
+    let mutex = OneShotMutex::new(42);
+    let mut locked_at = None;
+    let mut failed_at = None;
+    let events = capture(|| {
+        let (guard, location) = lock_at(&mutex);
+        locked_at = Some(location);
+        let (failed, location) = try_lock_at(&mutex);
+        failed_at = Some(location);
+        assert!(failed.is_none());
+        drop(guard);
+    });
+
+    assert_eq!(events.len(), 3);
+
+    assert_eq!(events[0].level, Level::TRACE);
+    assert_eq!(events[0].event.as_deref(), Some("Lock"));
+    assert_eq!(events[0].location, locked_at.unwrap().to_string());
+
+    assert_eq!(events[1].level, Level::WARN);
+    assert_eq!(events[1].message, "failed to acquire lock");
+    assert_eq!(events[1].operation.as_deref(), Some("try_lock"));
+    assert_eq!(events[1].location, failed_at.unwrap().to_string());
+
+    assert_eq!(events[2].level, Level::TRACE);
+    assert_eq!(events[2].event.as_deref(), Some("Unlock"));
+}
+
+#[test]
+fn rwlock() {
+    let lock = OneShotRwLock::new(42);
+    let events = capture(|| {
+        let guard = lock.read();
+        assert!(lock.try_write().is_none());
+        drop(guard);
+
+        let guard = lock.upgradable_read();
+        assert!(lock.try_upgradable_read().is_none());
+        let guard = lock_api::RwLockUpgradableReadGuard::upgrade(guard);
+        assert!(lock.try_read().is_none());
+        drop(guard);
+    });
+
+    let summary = events
+        .iter()
+        .map(|event| match event.level {
+            Level::WARN => format!("failed {}", event.operation.as_deref().unwrap()),
+            _ => event.event.clone().unwrap(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            "LockShared",
+            "failed try_lock_exclusive",
+            "UnlockShared",
+            "LockUpgradable",
+            "failed try_lock_upgradable",
+            "Upgrade",
+            "failed try_lock_shared",
+            "UnlockExclusive",
+        ]
+    );
+}
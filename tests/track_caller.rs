@@ -0,0 +1,54 @@
+#![cfg(panic = "unwind")]
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+
+use one_shot_mutex::{OneShotMutex, OneShotRwLock, OneShotRwLockUpgradableReadGuard};
+
+static LOCATION: Mutex<Option<(String, u32)>> = Mutex::new(None);
+
+/// Runs `f`, which must panic, and returns the line of the reported panic location.
+fn panic_line(f: impl FnOnce()) -> u32 {
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    assert!(result.is_err());
+
+    let (file, line) = LOCATION.lock().unwrap().take().unwrap();
+    assert_eq!(file, file!());
+    line
+}
+
+// The panic hook is global, so all checks live in a single test.
+#[test]
+fn track_caller() {
+    panic::set_hook(Box::new(|info| {
+        let location = info.location().unwrap();
+        *LOCATION.lock().unwrap() = Some((location.file().to_owned(), location.line()));
+    }));
+
+    let mutex = OneShotMutex::new(42);
+    let _guard = mutex.lock();
+    let line = line!() + 1;
+    assert_eq!(panic_line(|| drop(mutex.lock())), line);
+
+    let lock = OneShotRwLock::new(42);
+    let guard = lock.write();
+    let line = line!() + 1;
+    assert_eq!(panic_line(|| drop(lock.read())), line);
+    let line = line!() + 1;
+    assert_eq!(panic_line(|| drop(lock.upgradable_read())), line);
+    drop(guard);
+
+    let guard = lock.read();
+    let line = line!() + 1;
+    assert_eq!(panic_line(|| drop(lock.write())), line);
+
+    let upgradable = lock.upgradable_read();
+    let line = line!() + 2;
+    assert_eq!(
+        panic_line(|| drop(OneShotRwLockUpgradableReadGuard::upgrade(upgradable))),
+        line
+    );
+    drop(guard);
+
+    let _ = panic::take_hook();
+}
@@ -0,0 +1,91 @@
+#![cfg(panic = "unwind")]
+
+use std::panic::{self, AssertUnwindSafe};
+
+use one_shot_mutex::{OneShotGate, OneShotMutex, OneShotRwLock};
+
+#[test]
+fn mutex() {
+    let mutex = OneShotMutex::new(42);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut guard = mutex.lock();
+        *guard += 1;
+        panic!("panicking while holding the guard");
+    }));
+    assert!(result.is_err());
+
+    let guard = mutex.try_lock().unwrap();
+    assert_eq!(*guard, 43);
+}
+
+#[test]
+fn rwlock_exclusive() {
+    let lock = OneShotRwLock::new(42);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut guard = lock.write();
+        *guard += 1;
+        panic!("panicking while holding the guard");
+    }));
+    assert!(result.is_err());
+
+    let guard = lock.try_write().unwrap();
+    assert_eq!(*guard, 43);
+}
+
+#[test]
+fn rwlock_shared() {
+    let lock = OneShotRwLock::new(42);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = lock.read();
+        let _guard2 = lock.read();
+        panic!("panicking while holding the guards");
+    }));
+    assert!(result.is_err());
+
+    assert!(lock.try_write().is_some());
+}
+
+#[test]
+fn rwlock_upgradable() {
+    let lock = OneShotRwLock::new(42);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = lock.upgradable_read();
+        panic!("panicking while holding the guard");
+    }));
+    assert!(result.is_err());
+
+    assert!(lock.try_write().is_some());
+}
+
+#[test]
+fn contention_panic() {
+    let mutex = OneShotMutex::new(42);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = mutex.lock();
+        let _guard2 = mutex.lock();
+    }));
+    assert!(result.is_err());
+
+    assert!(mutex.try_lock().is_some());
+}
+
+#[test]
+fn gate() {
+    let gate = OneShotGate::new();
+
+    let result = panic::catch_unwind(|| {
+        gate.run_once(|| panic!("panicking while running"));
+    });
+    assert!(result.is_err());
+    assert!(!gate.is_completed());
+
+    let mut runs = 0;
+    gate.run_once(|| runs += 1);
+    assert_eq!(runs, 1);
+    assert!(gate.is_completed());
+}